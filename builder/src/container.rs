@@ -0,0 +1,340 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Attribute, Error, Generics, Ident};
+
+use crate::field::SetterAttribute;
+
+#[derive(Clone, Default)]
+pub struct ContainerAttribute {
+    pub typestate: bool,
+    pub pattern: BuilderPattern,
+    pub setter: SetterAttribute,
+    pub validate: Option<syn::Path>,
+    pub name: Option<Ident>,
+    pub vis: Option<syn::Visibility>,
+    pub constructor: Option<Ident>,
+    pub build_fn: Option<Ident>,
+    pub getters: bool,
+    /// `#[builder(merge)]`: the builder gets `merge` and `or` to be layered on
+    /// top of another one.
+    pub merge: bool,
+    /// `#[builder(from)]`: the builder implements `From<Struct>`, which moves
+    /// the fields out of the value and so cannot be derived for a struct that
+    /// implements `Drop`.
+    pub from: bool,
+    /// Derives added onto the builder by `#[builder(derive(...))]`.
+    pub derives: Vec<syn::Path>,
+    /// `#[builder(no_std)]`: the generated code only relies on `core` and
+    /// `alloc`.
+    pub no_std: bool,
+}
+
+/// How the generated setters and `build` receive the builder.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum BuilderPattern {
+    /// `&mut self` setters and `build(&mut self)`
+    #[default]
+    Mutable,
+    /// `self` setters and `build(self)`
+    Owned,
+    /// `&self` setters returning a modified clone and `build(&self)`
+    Immutable,
+}
+
+impl ContainerAttribute {
+    pub fn parse_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut res = Self::default();
+        let mut pattern_span = None;
+        let mut getters_span = None;
+        let mut derive_span = None;
+        let mut merge_span = None;
+        for attribute in attrs {
+            if !attribute.path().is_ident("builder") {
+                continue;
+            }
+            if let syn::AttrStyle::Inner(_) = &attribute.style {
+                return Err(Error::new(
+                    attribute.span(),
+                    "`builder` attribute only accepts outer one (the one without `!`)",
+                ));
+            }
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("typestate") {
+                    res.typestate = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("pattern") {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    res.pattern = BuilderPattern::parse(&val)?;
+                    pattern_span = Some(val.span());
+                    return Ok(());
+                }
+                if meta.path.is_ident("setter") {
+                    let span = meta.path.span();
+                    res.setter.parse_meta(meta)?;
+                    let field_options = [
+                        ("name", res.setter.name.is_some()),
+                        ("opt", res.setter.opt.is_some()),
+                        ("clear", res.setter.clear.is_some()),
+                        ("transform", res.setter.transform.is_some()),
+                    ];
+                    if let Some((option, _)) = field_options.iter().find(|(_, given)| *given) {
+                        return Err(Error::new(
+                            span,
+                            format!("`setter({} = ...)` can only be given on fields", option),
+                        ));
+                    }
+                    return Ok(());
+                }
+                if meta.path.is_ident("validate") {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    res.validate = Some(val.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("name") {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    res.name = Some(val.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("vis") {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    res.vis = Some(val.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("constructor") {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    res.constructor = Some(val.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("build_fn") {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    res.build_fn = Some(val.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("getters") {
+                    res.getters = true;
+                    getters_span = Some(meta.path.span());
+                    return Ok(());
+                }
+                if meta.path.is_ident("merge") {
+                    res.merge = true;
+                    merge_span = Some(meta.path.span());
+                    return Ok(());
+                }
+                if meta.path.is_ident("from") {
+                    res.from = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("no_std") {
+                    res.no_std = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("derive") {
+                    if !cfg!(feature = "derive") {
+                        return Err(meta.error(
+                            "`derive(...)` requires the `derive` feature of `derive_builder`",
+                        ));
+                    }
+                    derive_span = Some(meta.path.span());
+                    return meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("Default") {
+                            return Err(meta.error("the builder already implements `Default`"));
+                        }
+                        res.derives.push(meta.path);
+                        Ok(())
+                    });
+                }
+                Err(meta.error("unrecognized `builder` option"))
+            })?;
+        }
+        if let Some(span) = pattern_span {
+            if res.typestate && res.pattern != BuilderPattern::Owned {
+                return Err(Error::new(
+                    span,
+                    "`#[builder(typestate)]` only supports `pattern = \"owned\"`",
+                ));
+            }
+        }
+        let typestate_unsupported = [
+            ("getters", getters_span),
+            ("derive", derive_span),
+            ("merge", merge_span),
+        ];
+        for (option, span) in typestate_unsupported {
+            if let (true, Some(span)) = (res.typestate, span) {
+                return Err(Error::new(
+                    span,
+                    format!("`#[builder(typestate)]` does not support `{}`", option),
+                ));
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl ContainerAttribute {
+    /// Wraps the expression constructing the struct into the body of a `build`
+    /// method returning `Result`, running the validation hook if there is one.
+    pub fn build_body(&self, error_ident: &Ident, built: TokenStream) -> TokenStream {
+        let Some(validate) = &self.validate else {
+            return quote! {
+                ::core::result::Result::Ok(#built)
+            };
+        };
+        quote! {
+            let __built = #built;
+            #validate(&__built).map_err(|e| {
+                #error_ident::Validation(::core::convert::Into::into(e))
+            })?;
+            ::core::result::Result::Ok(__built)
+        }
+    }
+}
+
+impl ContainerAttribute {
+    /// Attributes of the default builder struct: the derives given in
+    /// `#[builder(derive(...))]`, plus `Clone` which the immutable pattern
+    /// relies on. Deriving `Deserialize` makes missing fields keep their
+    /// default, so that a builder can be loaded from a partial config.
+    pub fn builder_attrs(&self) -> TokenStream {
+        let has_derive = |name| self.has_derive(name);
+        let clone = (self.pattern == BuilderPattern::Immutable && !has_derive("Clone"))
+            .then(|| quote!(::core::clone::Clone));
+        let derives = self.derives.iter().map(|path| quote!(#path)).chain(clone);
+        let serde_default = has_derive("Deserialize").then(|| {
+            quote! {
+                #[serde(default)]
+            }
+        });
+        quote! {
+            #[derive(#(#derives),*)]
+            #serde_default
+        }
+    }
+
+    /// Attributes of the `PhantomData` field of the builder, which serde
+    /// leaves out.
+    pub fn phantom_attrs(&self) -> TokenStream {
+        if self.has_derive("Serialize") || self.has_derive("Deserialize") {
+            quote!(#[serde(skip)])
+        } else {
+            quote!()
+        }
+    }
+
+    fn has_derive(&self, name: &str) -> bool {
+        self.derives
+            .iter()
+            .any(|path| path.segments.last().is_some_and(|seg| seg.ident == name))
+    }
+}
+
+impl ContainerAttribute {
+    /// Path to the crate providing `String` and `format!`, `std` or `alloc`.
+    pub fn alloc_crate(&self) -> TokenStream {
+        if self.no_std {
+            quote!(::alloc)
+        } else {
+            quote!(::std)
+        }
+    }
+
+    /// Type keeping the error of a `try_default_with` function or of the
+    /// builder of a field, a boxed `Error`, or only its message as
+    /// `core::error::Error` is not stable yet.
+    pub fn boxed_error_ty(&self) -> TokenStream {
+        if self.no_std {
+            quote!(::alloc::string::String)
+        } else {
+            quote!(
+                ::std::boxed::Box<
+                    dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync,
+                >
+            )
+        }
+    }
+
+    /// Converts the error `e` into [`Self::boxed_error_ty`].
+    pub fn boxed_error(&self, e: TokenStream) -> TokenStream {
+        if self.no_std {
+            quote!(::alloc::string::ToString::to_string(&#e))
+        } else {
+            quote!(::std::boxed::Box::new(#e))
+        }
+    }
+}
+
+impl BuilderPattern {
+    fn parse(lit: &syn::LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "mutable" => Ok(Self::Mutable),
+            "owned" => Ok(Self::Owned),
+            "immutable" => Ok(Self::Immutable),
+            _ => Err(Error::new(
+                lit.span(),
+                r#"expected `"mutable"`, `"owned"` or `"immutable"`"#,
+            )),
+        }
+    }
+
+    /// Generates a setter method whose `body` updates the builder referred to
+    /// by the token stream it is given.
+    pub fn setter(
+        self,
+        name: &Ident,
+        args: TokenStream,
+        body: impl FnOnce(TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        self.generic_setter(name, &Generics::default(), args, body)
+    }
+
+    /// Same as [`Self::setter`], but the setter has its own generic parameters.
+    pub fn generic_setter(
+        self,
+        name: &Ident,
+        generics: &Generics,
+        args: TokenStream,
+        body: impl FnOnce(TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        let where_clause = &generics.where_clause;
+        match self {
+            Self::Mutable => {
+                let body = body(quote!(self));
+                quote! {
+                    pub fn #name #generics(&mut self, #args) -> &mut Self #where_clause {
+                        #body;
+                        self
+                    }
+                }
+            }
+            Self::Owned => {
+                let body = body(quote!(self));
+                quote! {
+                    pub fn #name #generics(mut self, #args) -> Self #where_clause {
+                        #body;
+                        self
+                    }
+                }
+            }
+            Self::Immutable => {
+                let body = body(quote!(__builder));
+                quote! {
+                    pub fn #name #generics(&self, #args) -> Self #where_clause {
+                        let mut __builder = ::core::clone::Clone::clone(self);
+                        #body;
+                        __builder
+                    }
+                }
+            }
+        }
+    }
+
+    /// The receiver of the generated `build` method.
+    pub fn build_receiver(self) -> TokenStream {
+        match self {
+            Self::Mutable => quote!(&mut self),
+            Self::Owned => quote!(self),
+            Self::Immutable => quote!(&self),
+        }
+    }
+}
@@ -1,13 +1,23 @@
 #![allow(unused)]
 
-use syn::{spanned::Spanned, Attribute, Error, Ident, Type};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{ext::IdentExt, meta::ParseNestedMeta, spanned::Spanned, Attribute, Error, Ident, Type};
+
+use crate::container::BuilderPattern;
 
 #[derive(Clone)]
 pub struct Field {
-    pub attrs: Option<FieldAttribute>,
+    pub attrs: FieldAttribute,
+    /// Attributes of the field, such as doc comments, which are copied onto
+    /// its setter and `each` method.
+    pub forward_attrs: Vec<Attribute>,
     pub vis: syn::Visibility,
+    /// Name of the field in the builder, `_0`, `_1`, ... for tuple fields.
     pub ident: Ident,
-    pub colon_token: syn::Token![:],
+    /// Name of the field in the struct being built.
+    pub member: syn::Member,
+    pub colon_token: Option<syn::Token![:]>,
     pub ty: FieldTypeKind,
     pub span: proc_macro2::Span,
 }
@@ -15,115 +25,687 @@ pub struct Field {
 #[derive(Clone)]
 pub enum FieldTypeKind {
     OptionWrapped {
-        option: Ident,
+        /// Path to the type without its arguments, such as `std::option::Option`
+        option: syn::Path,
         angle_open: syn::Token![<],
         ty: Type,
         angle_close: syn::Token![>],
     },
     VecWrapped {
-        vec: Ident,
+        vec: syn::Path,
         angle_open: syn::Token![<],
         ty: Type,
         angle_close: syn::Token![>],
     },
+    /// Collections other than `Vec` given an `each` method, which fills them
+    /// in through `Extend`, such as `HashMap<K, V>` or `VecDeque<T>`.
+    Collection {
+        ty: Type,
+        item: CollectionItem,
+    },
     Raw(Type),
+    /// A field given `#[builder(sub_builder)]`, stored as the builder of its
+    /// own type.
+    SubBuilder {
+        ty: Type,
+        builder: Type,
+        /// The `build` method of `builder`.
+        build_fn: Ident,
+    },
 }
 
 #[derive(Clone)]
+pub enum CollectionItem {
+    /// `HashSet<T>`, `BTreeSet<T>`, `VecDeque<T>`, ...
+    Single(Type),
+    /// `HashMap<K, V>` and `BTreeMap<K, V>`
+    Pair(Type, Type),
+    /// Any other type given `#[builder(each = "...")]`, which is expected to
+    /// implement `Extend`.
+    Unknown,
+}
+
+#[derive(Clone, Default)]
 pub struct FieldAttribute {
-    pub pound_token: syn::Token![#],
-    pub bracket_token: syn::token::Bracket,
-    pub builder: Ident,
-    pub paren_token: syn::token::Paren,
-    pub each_ident: Ident,
-    pub each_eq: syn::Token![=],
-    pub each_val: Ident,
+    pub each: Option<Ident>,
+    pub default: Option<FieldDefault>,
+    pub setter: SetterAttribute,
+    /// `#[builder(skip)]`: the field is not stored in the builder and is only
+    /// computed from its default in `build`.
+    pub skip: bool,
+    pub merge: MergeStrategy,
+    /// `#[builder(kind = "...")]` overriding how the type of the field is
+    /// classified, for type aliases and shadowed names.
+    pub kind: Option<syn::LitStr>,
+    pub sub_builder: Option<SubBuilderAttribute>,
+}
+
+/// Options given in `#[builder(sub_builder)]`, either as
+/// `sub_builder = "..."` naming the builder or as
+/// `sub_builder(builder = "...", build_fn = "...")`.
+#[derive(Clone, Default)]
+pub struct SubBuilderAttribute {
+    /// Builder of the field, `{Type}Builder` unless given.
+    pub builder: Option<Type>,
+    /// Name of the `build` method of that builder, if it was renamed.
+    pub build_fn: Option<Ident>,
+}
+
+/// How `merge` combines a collection field of two builders, given by
+/// `#[builder(merge = "...")]`.
+#[derive(Clone, Copy, Default)]
+pub enum MergeStrategy {
+    /// the other collection replaces this one unless it is empty
+    #[default]
+    Replace,
+    /// the other collection is appended to this one
+    Append(proc_macro2::Span),
+}
+
+/// Options given in `#[builder(setter(...))]`, either on a field or on the
+/// whole struct.
+#[derive(Clone, Default)]
+pub struct SetterAttribute {
+    pub into: bool,
+    pub name: Option<Ident>,
+    /// Name of the setter of an `Option<T>` field taking the `Option` itself.
+    pub opt: Option<Ident>,
+    /// Name of the method resetting an `Option<T>` field to `None`.
+    pub clear: Option<Ident>,
+    /// Closure whose typed arguments the setter takes in place of the value,
+    /// and whose body makes the value out of them.
+    pub transform: Option<syn::ExprClosure>,
 }
 
 #[derive(Clone)]
-pub struct FieldAttrInner {
-    pub key: Ident,
-    pub eq: syn::Token![=],
-    pub val: syn::LitStr,
+pub enum FieldDefault {
+    /// `#[builder(default)]`
+    Trait(proc_macro2::Span),
+    /// `#[builder(default = "...")]`
+    Expr(syn::Expr),
+    /// `#[builder(default_with = "...")]`, an async function only awaited by
+    /// `build_async`, or `#[builder(try_default_with = "...")]` if it returns
+    /// a `Result` whose error `build_async` passes on
+    Async { path: syn::Path, fallible: bool },
 }
 
 impl Field {
-    pub fn parse_field(field: syn::Field) -> syn::Result<Self> {
+    pub fn parse_field(field: syn::Field, index: usize) -> syn::Result<Self> {
         let span = field.span();
         let syn::Field {
-            mut attrs,
+            attrs,
             vis,
             mutability: _,
             ident,
             colon_token,
             ty,
         } = field;
-        let attrs = attrs
-            .pop()
-            .map(FieldAttribute::parse_attribute)
-            .transpose()?;
-        let ty = FieldTypeKind::parse(ty);
+        let forward_attrs = attrs
+            .iter()
+            .filter(|attribute| {
+                let path = attribute.path();
+                path.is_ident("doc") || path.is_ident("deprecated")
+            })
+            .cloned()
+            .collect();
+        let attrs = FieldAttribute::parse_attributes(&attrs)?;
+        let (ident, member) = match ident {
+            Some(ident) => (ident.clone(), syn::Member::Named(ident)),
+            None => (
+                format_ident!("_{}", index, span = span),
+                syn::Member::Unnamed(syn::Index {
+                    index: index as u32,
+                    span,
+                }),
+            ),
+        };
+        let ty = match &attrs.kind {
+            Some(kind) => {
+                let kind_ty = match kind.value().as_str() {
+                    "option" => FieldTypeKind::option(ty),
+                    "vec" => FieldTypeKind::vec(ty),
+                    _ => FieldTypeKind::Raw(ty),
+                };
+                if kind_ty.is_raw() && kind.value() != "raw" {
+                    return Err(Error::new(
+                        kind.span(),
+                        "this `kind` needs a type with a single type argument",
+                    ));
+                }
+                kind_ty
+            }
+            None => FieldTypeKind::parse(ty),
+        };
+        // without an `each` method, a collection is set as a whole and is
+        // required like any other field
+        let ty = match ty {
+            FieldTypeKind::Collection { ty, .. } if attrs.each.is_none() => FieldTypeKind::Raw(ty),
+            ty => ty,
+        };
+        let ty = match (ty, &attrs.sub_builder) {
+            (FieldTypeKind::Raw(ty), Some(sub_builder)) => {
+                if attrs.skip || attrs.each.is_some() || attrs.default.is_some() {
+                    return Err(Error::new(
+                        span,
+                        "`#[builder(sub_builder)]` cannot be combined with `skip`, `each` or `default`",
+                    ));
+                }
+                let builder = match &sub_builder.builder {
+                    Some(builder) => builder.clone(),
+                    None => sub_builder_ty(&ty).ok_or_else(|| {
+                        Error::new(
+                            ty.span(),
+                            r#"cannot tell the builder of this type, give it as `sub_builder = "..."`"#,
+                        )
+                    })?,
+                };
+                let build_fn = sub_builder
+                    .build_fn
+                    .clone()
+                    .unwrap_or_else(|| format_ident!("build"));
+                FieldTypeKind::SubBuilder {
+                    ty,
+                    builder,
+                    build_fn,
+                }
+            }
+            (_, Some(_)) => {
+                return Err(Error::new(
+                    span,
+                    "`#[builder(sub_builder)]` cannot be used for `Option<T>` or collections",
+                ));
+            }
+            (ty, None) => ty,
+        };
+        let ty = match ty {
+            FieldTypeKind::Raw(ty) if attrs.each.is_some() => FieldTypeKind::Collection {
+                ty,
+                item: CollectionItem::Unknown,
+            },
+            ty => ty,
+        };
+        if let (true, Some(each)) = (attrs.skip, &attrs.each) {
+            return Err(Error::new(
+                each.span(),
+                r#"`#[builder(each = "...")]` cannot be used for skipped fields"#,
+            ));
+        }
+        if let (FieldTypeKind::OptionWrapped { .. }, Some(each)) = (&ty, &attrs.each) {
+            return Err(Error::new(
+                each.span(),
+                r#"`#[builder(each = "...")]` cannot be used for `Option<T>`"#,
+            ));
+        }
+        if let (MergeStrategy::Append(span), true) = (
+            attrs.merge,
+            attrs.skip || !(ty.is_vec_wrapped() || ty.is_collection()),
+        ) {
+            return Err(Error::new(
+                span,
+                r#"`#[builder(merge = "append")]` can only be used for `Vec<T>` or collections given `each`"#,
+            ));
+        }
+        if let (false, Some(name)) = (
+            ty.is_opt_wrapped(),
+            attrs.setter.opt.as_ref().or(attrs.setter.clear.as_ref()),
+        ) {
+            return Err(Error::new(
+                name.span(),
+                r#"`setter(opt = "...")` and `setter(clear = "...")` can only be used for `Option<T>`"#,
+            ));
+        }
+        if let (true, Some(default @ FieldDefault::Async { path, .. })) =
+            (attrs.skip, &attrs.default)
+        {
+            return Err(Error::new(
+                path.span(),
+                format!(
+                    r#"`#[builder({} = "...")]` cannot be used for skipped fields"#,
+                    default.option_name(),
+                ),
+            ));
+        }
+        if let (false, false, Some(default)) = (attrs.skip, ty.is_raw(), &attrs.default) {
+            return Err(Error::new(
+                default.span(),
+                "`#[builder(default)]` cannot be used for `Option<T>` or collections",
+            ));
+        }
         Ok(Self {
             attrs,
+            forward_attrs,
             vis,
-            ident: ident.unwrap(),
-            colon_token: colon_token.unwrap(),
+            ident,
+            member,
+            colon_token,
             ty,
             span,
         })
     }
+
+    /// Name of the setter taking the whole value of this field.
+    pub fn setter_ident(&self) -> &Ident {
+        self.attrs.setter.name.as_ref().unwrap_or(&self.ident)
+    }
+
+    /// Name of the field in the docs of the builder, such as `` `name` `` or
+    /// `` `0` ``.
+    pub fn doc_name(&self) -> String {
+        match &self.member {
+            syn::Member::Named(ident) => format!("`{}`", ident.unraw()),
+            syn::Member::Unnamed(index) => format!("`{}`", index.index),
+        }
+    }
+
+    /// Whether `build` fails unless this field has been set, which is still
+    /// the case with a `default_with` only awaited by `build_async`.
+    pub fn is_required(&self) -> bool {
+        !self.attrs.skip
+            && self.ty.is_raw()
+            && matches!(self.attrs.default, None | Some(FieldDefault::Async { .. }))
+    }
+
+    /// Generates the setter taking the whole value of this field, or the
+    /// method handing out the builder of a sub-builder field.
+    pub fn setter_method(&self, pattern: BuilderPattern) -> TokenStream {
+        let Self {
+            ident,
+            ty,
+            attrs,
+            forward_attrs,
+            ..
+        } = self;
+        if let FieldTypeKind::SubBuilder { builder, .. } = ty {
+            let setter_ident = self.setter_ident();
+            return quote! {
+                #(#forward_attrs)*
+                pub fn #setter_ident(&mut self) -> &mut #builder {
+                    &mut self.#ident
+                }
+            };
+        }
+        let (params, value) = attrs.setter.params(ident, ty.setter_ty());
+        let value = match ty {
+            FieldTypeKind::OptionWrapped { .. } | FieldTypeKind::Raw(_) => {
+                quote!(::core::option::Option::Some(#value))
+            }
+            FieldTypeKind::VecWrapped { .. }
+            | FieldTypeKind::Collection { .. }
+            | FieldTypeKind::SubBuilder { .. } => value,
+        };
+        let method = pattern.setter(self.setter_ident(), params, |this| {
+            quote! {
+                #this.#ident = #value
+            }
+        });
+        quote! {
+            #(#forward_attrs)*
+            #method
+        }
+    }
+
+    /// Generates the setter, `each` and `Option` methods of this field in that
+    /// order, along with their names. An `each` method named like the setter
+    /// takes its place.
+    pub fn builder_methods(&self, pattern: BuilderPattern) -> Vec<(Ident, TokenStream)> {
+        let each = self.attrs.each.clone().zip(self.each_method(pattern));
+        let setter_ident = self.setter_ident();
+        let setter = match &each {
+            Some((each, _)) if each == setter_ident => None,
+            _ => Some((setter_ident.clone(), self.setter_method(pattern))),
+        };
+        setter
+            .into_iter()
+            .chain(each)
+            .chain(self.option_methods(pattern))
+            .collect()
+    }
+
+    /// Generates the setters of an `Option<T>` field which take the `Option`
+    /// itself and reset it to `None`, named `*_opt` and `clear_*` unless
+    /// given in `setter(opt = "...", clear = "...")`.
+    pub fn option_methods(&self, pattern: BuilderPattern) -> Vec<(Ident, TokenStream)> {
+        let FieldTypeKind::OptionWrapped { ty, .. } = &self.ty else {
+            return Vec::new();
+        };
+        let Self { ident, attrs, .. } = self;
+        let setter_ident = self.setter_ident();
+        let opt = match &attrs.setter.opt {
+            Some(opt) => opt.clone(),
+            None => format_ident!("{}_opt", setter_ident.unraw()),
+        };
+        let clear = match &attrs.setter.clear {
+            Some(clear) => clear.clone(),
+            None => prefixed_ident("clear", setter_ident),
+        };
+
+        let arg_ty = attrs.setter.arg_ty(ty);
+        let value = if attrs.setter.into {
+            quote!(#ident.map(::core::convert::Into::into))
+        } else {
+            quote!(#ident)
+        };
+        let args = quote!(#ident: ::core::option::Option<#arg_ty>);
+        let opt_method = pattern.setter(&opt, args, |this| {
+            quote! {
+                #this.#ident = #value
+            }
+        });
+        let clear_method = pattern.setter(&clear, quote!(), |this| {
+            quote! {
+                #this.#ident = ::core::option::Option::None
+            }
+        });
+        let opt_doc = format!("Sets {} to the given `Option`.", self.doc_name());
+        let clear_doc = format!("Resets {} to `None`.", self.doc_name());
+        vec![
+            (opt, quote!(#[doc = #opt_doc] #opt_method)),
+            (clear, quote!(#[doc = #clear_doc] #clear_method)),
+        ]
+    }
+
+    /// Generates the `get_*` and `*_is_set` methods given by
+    /// `#[builder(getters)]`.
+    pub fn getter_methods(&self) -> Vec<(Ident, TokenStream)> {
+        let Self { ident, ty, .. } = self;
+        let getter = prefixed_ident("get", ident);
+        let is_set = format_ident!("{}_is_set", ident.unraw());
+        let name = self.doc_name();
+        let (getter_ty, get) = match ty {
+            FieldTypeKind::OptionWrapped { ty, .. } | FieldTypeKind::Raw(ty) => (
+                quote!(::core::option::Option<&#ty>),
+                quote!(self.#ident.as_ref()),
+            ),
+            FieldTypeKind::VecWrapped { .. } | FieldTypeKind::Collection { .. } => {
+                let storage_ty = ty.storage_ty();
+                (quote!(&#storage_ty), quote!(&self.#ident))
+            }
+            // a sub-builder is never unset, so it only gets the getter
+            FieldTypeKind::SubBuilder { builder, .. } => {
+                let getter_doc = format!("Returns the builder of {}.", name);
+                let getter_method = quote! {
+                    #[doc = #getter_doc]
+                    pub fn #getter(&self) -> &#builder {
+                        &self.#ident
+                    }
+                };
+                return vec![(getter, getter_method)];
+            }
+        };
+        let (bound, check) = self.is_set(quote!(self));
+        let where_clause = bound.map(|bound| quote!(where #bound));
+        let getter_doc = format!("Returns the value of {} set so far.", name);
+        let is_set_doc = format!("Whether {} has been set.", name);
+        let getter_method = quote! {
+            #[doc = #getter_doc]
+            pub fn #getter(&self) -> #getter_ty {
+                #get
+            }
+        };
+        let is_set_method = quote! {
+            #[doc = #is_set_doc]
+            pub fn #is_set(&self) -> bool #where_clause {
+                #check
+            }
+        };
+        vec![(getter, getter_method), (is_set, is_set_method)]
+    }
+
+    /// Expression checking whether this field of the builder `this` is set,
+    /// along with the bound it needs. Collections are set once they are not
+    /// empty, and those of an unknown type once they differ from their
+    /// default, as not all of them can be iterated by reference.
+    fn is_set(&self, this: TokenStream) -> (Option<TokenStream>, TokenStream) {
+        let Self { ident, ty, .. } = self;
+        match ty {
+            FieldTypeKind::OptionWrapped { .. } | FieldTypeKind::Raw(_) => {
+                (None, quote!(#this.#ident.is_some()))
+            }
+            FieldTypeKind::VecWrapped { .. }
+            | FieldTypeKind::Collection {
+                item: CollectionItem::Single(_) | CollectionItem::Pair(..),
+                ..
+            } => (None, quote!(!#this.#ident.is_empty())),
+            FieldTypeKind::Collection {
+                ty,
+                item: CollectionItem::Unknown,
+            } => (
+                // higher-ranked, so that it is not rejected as a trivially
+                // false bound for a concrete type
+                Some(quote! {
+                    for<'__a> #ty: ::core::default::Default + ::core::cmp::PartialEq
+                }),
+                quote! {
+                    #this.#ident != <#ty as ::core::default::Default>::default()
+                },
+            ),
+            // merged as a whole by `merge_field`, and has no `*_is_set`
+            FieldTypeKind::SubBuilder { .. } => unreachable!(),
+        }
+    }
+
+    /// Generates the statement of `merge` overlaying this field of `other`
+    /// onto `self`, along with the bound it needs.
+    pub fn merge_field(&self) -> (Option<TokenStream>, TokenStream) {
+        let Self {
+            ident, ty, attrs, ..
+        } = self;
+        let append = quote! {
+            ::core::iter::Extend::extend(&mut self.#ident, other.#ident);
+        };
+        match (attrs.merge, ty) {
+            // the builder of the field needs `#[builder(merge)]` as well
+            (_, FieldTypeKind::SubBuilder { .. }) => (
+                None,
+                quote! {
+                    self.#ident.merge(other.#ident);
+                },
+            ),
+            (
+                MergeStrategy::Append(_),
+                FieldTypeKind::Collection {
+                    ty,
+                    item: CollectionItem::Unknown,
+                },
+            ) => {
+                // extended with the other value as a whole, as `String` does,
+                // since not all of them can be iterated
+                let bound = quote!(for<'__a> #ty: ::core::iter::Extend<#ty>);
+                let append = quote! {
+                    ::core::iter::Extend::extend(
+                        &mut self.#ident,
+                        ::core::iter::once(other.#ident),
+                    );
+                };
+                (Some(bound), append)
+            }
+            (MergeStrategy::Append(_), _) => (None, append),
+            (MergeStrategy::Replace, _) => {
+                let (bound, check) = self.is_set(quote!(other));
+                let replace = quote! {
+                    if #check {
+                        self.#ident = other.#ident;
+                    }
+                };
+                (bound, replace)
+            }
+        }
+    }
+
+    /// Generates the one-at-a-time method given by `#[builder(each = "...")]`.
+    pub fn each_method(&self, pattern: BuilderPattern) -> Option<TokenStream> {
+        let Self {
+            ident,
+            ty,
+            attrs,
+            forward_attrs,
+            ..
+        } = self;
+        let each = attrs.each.as_ref()?;
+        let setter = &attrs.setter;
+        let extend = |this: TokenStream, item: TokenStream| {
+            quote! {
+                ::core::iter::Extend::extend(&mut #this.#ident, ::core::iter::once(#item))
+            }
+        };
+        let method = match ty {
+            FieldTypeKind::VecWrapped { ty, .. } => {
+                let arg_ty = setter.arg_ty(ty);
+                let value = setter.convert(each);
+                pattern.setter(each, quote!(#each: #arg_ty), |this| {
+                    quote! {
+                        #this.#ident.push(#value)
+                    }
+                })
+            }
+            FieldTypeKind::Collection {
+                item: CollectionItem::Single(ty),
+                ..
+            } => {
+                let arg_ty = setter.arg_ty(ty);
+                let value = setter.convert(each);
+                pattern.setter(each, quote!(#each: #arg_ty), |this| extend(this, value))
+            }
+            FieldTypeKind::Collection {
+                item: CollectionItem::Pair(key_ty, value_ty),
+                ..
+            } => {
+                let (key, value) = (format_ident!("key"), format_ident!("value"));
+                let key_arg_ty = setter.arg_ty(key_ty);
+                let value_arg_ty = setter.arg_ty(value_ty);
+                let item = {
+                    let key = setter.convert(&key);
+                    let value = setter.convert(&value);
+                    quote!((#key, #value))
+                };
+                let args = quote!(#key: #key_arg_ty, #value: #value_arg_ty);
+                pattern.setter(each, args, |this| extend(this, item))
+            }
+            FieldTypeKind::Collection {
+                ty,
+                item: CollectionItem::Unknown,
+            } => {
+                let mut generics: syn::Generics = syn::parse_quote!(<__T>);
+                generics.where_clause = Some(syn::parse_quote! {
+                    where #ty: ::core::iter::Extend<__T>
+                });
+                pattern.generic_setter(each, &generics, quote!(#each: __T), |this| {
+                    extend(this, quote!(#each))
+                })
+            }
+            // rejected in `Field::parse_field`
+            FieldTypeKind::OptionWrapped { .. }
+            | FieldTypeKind::Raw(_)
+            | FieldTypeKind::SubBuilder { .. } => return None,
+        };
+        Some(quote! {
+            #(#forward_attrs)*
+            #method
+        })
+    }
 }
 
 impl FieldTypeKind {
+    /// Classifies `ty` by its path, which names a standard type either on its
+    /// own, as imported by the prelude or a `use`, or fully qualified by
+    /// `std`, `core` or `alloc`.
     pub fn parse(ty: Type) -> Self {
-        macro_rules! filter_try {
-            (let $p:pat = $e:expr) => {
-                let $p = $e else {
-                    return Self::Raw(ty);
+        let Some(name) = std_type_name(&ty) else {
+            return Self::Raw(ty);
+        };
+        match name.to_string().as_str() {
+            "Option" => Self::option(ty),
+            "Vec" => Self::vec(ty),
+            "HashMap" | "BTreeMap" => {
+                let item = match type_args(&ty).as_slice() {
+                    [key, value] => CollectionItem::Pair((*key).clone(), (*value).clone()),
+                    _ => return Self::Raw(ty),
                 };
-            };
-            (if $e:expr) => {
-                if $e {
-                    return Self::Raw(ty);
-                }
-            };
+                Self::Collection { ty, item }
+            }
+            "HashSet" | "BTreeSet" | "VecDeque" | "LinkedList" | "BinaryHeap" => {
+                let item = match type_args(&ty).first() {
+                    Some(elem) => CollectionItem::Single((*elem).clone()),
+                    None => return Self::Raw(ty),
+                };
+                Self::Collection { ty, item }
+            }
+            _ => Self::Raw(ty),
         }
+    }
 
-        use syn::{punctuated::Pair, GenericArgument, PathArguments};
-
-        filter_try!(let Type::Path(mut p) = ty.clone());
-        filter_try!(if p.qself.is_some() || p.path.leading_colon.is_some());
-        filter_try!(let Some(Pair::End(seg)) = p.path.segments.pop());
-        if seg.ident == "Option" {
-            filter_try!(let PathArguments::AngleBracketed(mut seg_args) = seg.arguments);
-            filter_try!(if seg_args.args.len() != 1);
-            filter_try!(let Some(Pair::End(GenericArgument::Type(arg_ty))) = seg_args.args.pop());
-            return Self::OptionWrapped {
-                option: seg.ident,
-                angle_open: seg_args.lt_token,
-                ty: arg_ty,
-                angle_close: seg_args.gt_token,
-            };
+    /// Takes `ty` as an `Option<T>`, or a type alias of one, unless it does
+    /// not have a single type argument.
+    pub fn option(ty: Type) -> Self {
+        match single_type_arg(&ty) {
+            Some((option, angle_open, ty, angle_close)) => Self::OptionWrapped {
+                option,
+                angle_open,
+                ty,
+                angle_close,
+            },
+            None => Self::Raw(ty),
         }
-        if seg.ident == "Vec" {
-            filter_try!(let PathArguments::AngleBracketed(mut seg_args) = seg.arguments);
-            filter_try!(if seg_args.args.len() != 1);
-            filter_try!(let Some(Pair::End(GenericArgument::Type(arg_ty))) = seg_args.args.pop());
-            return Self::VecWrapped {
-                vec: seg.ident,
-                angle_open: seg_args.lt_token,
-                ty: arg_ty,
-                angle_close: seg_args.gt_token,
-            };
+    }
+
+    /// Takes `ty` as a `Vec<T>`, or a type alias of one, unless it does not
+    /// have a single type argument.
+    pub fn vec(ty: Type) -> Self {
+        match single_type_arg(&ty) {
+            Some((vec, angle_open, ty, angle_close)) => Self::VecWrapped {
+                vec,
+                angle_open,
+                ty,
+                angle_close,
+            },
+            None => Self::Raw(ty),
+        }
+    }
+
+    /// Type of the builder field which stores this field until `build`.
+    pub fn storage_ty(&self) -> TokenStream {
+        match self {
+            Self::OptionWrapped { ty, .. } | Self::Raw(ty) => quote!(::core::option::Option<#ty>),
+            Self::VecWrapped {
+                vec,
+                angle_open,
+                ty,
+                angle_close,
+            } => quote!(#vec #angle_open #ty #angle_close),
+            Self::Collection { ty, .. } => quote!(#ty),
+            Self::SubBuilder { builder, .. } => quote!(#builder),
+        }
+    }
+
+    /// Expression storing the final `value` of this field into the builder.
+    pub fn to_storage(&self, value: &Ident) -> TokenStream {
+        match self {
+            Self::Raw(_) => quote!(::core::option::Option::Some(#value)),
+            Self::SubBuilder { .. } => quote!(::core::convert::From::from(#value)),
+            _ => quote!(#value),
+        }
+    }
+
+    /// Type of the value taken by the setter of this field.
+    pub fn setter_ty(&self) -> TokenStream {
+        match self {
+            Self::OptionWrapped { ty, .. } | Self::Raw(ty) => quote!(#ty),
+            Self::VecWrapped {
+                vec,
+                angle_open,
+                ty,
+                angle_close,
+            } => quote!(#vec #angle_open #ty #angle_close),
+            Self::Collection { ty, .. } | Self::SubBuilder { ty, .. } => quote!(#ty),
         }
-        Self::Raw(ty)
     }
 
     pub fn as_inner(&self) -> &Type {
         match self {
             Self::OptionWrapped { ty: oty, .. } => oty,
             Self::VecWrapped { ty: vty, .. } => vty,
-            Self::Raw(rty) => rty,
+            Self::Collection { ty: cty, .. } => cty,
+            Self::Raw(rty) | Self::SubBuilder { ty: rty, .. } => rty,
         }
     }
 
@@ -131,7 +713,8 @@ impl FieldTypeKind {
         match self {
             Self::OptionWrapped { ty: oty, .. } => oty,
             Self::VecWrapped { ty: vty, .. } => vty,
-            Self::Raw(rty) => rty,
+            Self::Collection { ty: cty, .. } => cty,
+            Self::Raw(rty) | Self::SubBuilder { ty: rty, .. } => rty,
         }
     }
 
@@ -146,54 +729,342 @@ impl FieldTypeKind {
     pub fn is_opt_wrapped(&self) -> bool {
         matches!(self, Self::OptionWrapped { .. })
     }
+
+    pub fn is_collection(&self) -> bool {
+        matches!(self, Self::Collection { .. })
+    }
+}
+
+impl MergeStrategy {
+    fn parse(lit: &syn::LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "replace" => Ok(Self::Replace),
+            "append" => Ok(Self::Append(lit.span())),
+            _ => Err(Error::new(
+                lit.span(),
+                r#"expected `"replace"` or `"append"`"#,
+            )),
+        }
+    }
+}
+
+impl FieldDefault {
+    pub fn span(&self) -> proc_macro2::Span {
+        match self {
+            Self::Trait(span) => *span,
+            Self::Expr(expr) => expr.span(),
+            Self::Async { path, .. } => path.span(),
+        }
+    }
+
+    /// Expression producing the value of a field that was never set.
+    pub fn to_expr(&self) -> TokenStream {
+        match self {
+            Self::Trait(_) => quote!(::core::default::Default::default()),
+            Self::Expr(expr) => quote!(#expr),
+            Self::Async { path, .. } => quote!(#path().await),
+        }
+    }
+
+    /// Name of the option giving this default.
+    pub fn option_name(&self) -> &'static str {
+        match self {
+            Self::Trait(_) | Self::Expr(_) => "default",
+            Self::Async {
+                fallible: false, ..
+            } => "default_with",
+            Self::Async { fallible: true, .. } => "try_default_with",
+        }
+    }
+}
+
+impl SetterAttribute {
+    /// Parses the contents of `setter(...)`.
+    pub fn parse_meta(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("into") {
+                self.into = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("name") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.name = Some(val.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("opt") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.opt = Some(val.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("clear") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.clear = Some(val.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("transform") {
+                let closure: syn::ExprClosure = meta.value()?.parse()?;
+                if let Some(async_token) = closure.asyncness {
+                    return Err(Error::new(
+                        async_token.span(),
+                        "`transform` cannot be an async closure",
+                    ));
+                }
+                let untyped = closure
+                    .inputs
+                    .iter()
+                    .find(|input| !matches!(input, syn::Pat::Type(_)));
+                if let Some(input) = untyped {
+                    return Err(Error::new(
+                        input.span(),
+                        "the arguments of `transform` need type annotations",
+                    ));
+                }
+                self.transform = Some(closure);
+                return Ok(());
+            }
+            Err(meta.error("unrecognized `setter` option"))
+        })
+    }
+
+    /// Parameters of the setter of a value of type `ty`, along with the
+    /// expression making the value out of them. The parameter is `arg` unless
+    /// they are given by `transform`, which takes the place of `into`.
+    pub fn params(&self, arg: &Ident, ty: impl ToTokens) -> (TokenStream, TokenStream) {
+        let Some(transform) = &self.transform else {
+            let arg_ty = self.arg_ty(ty);
+            return (quote!(#arg: #arg_ty), self.convert(arg));
+        };
+        let syn::ExprClosure {
+            inputs,
+            output,
+            body,
+            ..
+        } = transform;
+        let value = match output {
+            syn::ReturnType::Default => quote!(#body),
+            syn::ReturnType::Type(_, output_ty) => quote! {
+                {
+                    let __value: #output_ty = #body;
+                    __value
+                }
+            },
+        };
+        (quote!(#inputs), value)
+    }
+
+    /// Type of the setter parameter which ends up as a value of `ty`.
+    pub fn arg_ty(&self, ty: impl ToTokens) -> TokenStream {
+        if self.into {
+            quote!(impl ::core::convert::Into<#ty>)
+        } else {
+            quote!(#ty)
+        }
+    }
+
+    /// Converts the setter parameter `arg` into the value to be stored.
+    pub fn convert(&self, arg: &Ident) -> TokenStream {
+        if self.into {
+            quote!(::core::convert::Into::into(#arg))
+        } else {
+            quote!(#arg)
+        }
+    }
+}
+
+impl SubBuilderAttribute {
+    /// Parses the contents of `sub_builder(...)`.
+    fn parse_meta(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("builder") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.builder = Some(val.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("build_fn") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.build_fn = Some(val.parse()?);
+                return Ok(());
+            }
+            Err(meta.error("unrecognized `sub_builder` option"))
+        })
+    }
 }
 
 impl FieldAttribute {
-    pub fn parse_attribute(attribute: Attribute) -> syn::Result<Self> {
-        let span = attribute.meta.span();
-        let err = || Error::new(span, r#"expected `builder(each = "...")`"#);
+    /// Parses and merges every `#[builder(...)]` attribute among `attrs`.
+    pub fn parse_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut res = Self::default();
+        let results = attrs
+            .iter()
+            .filter(|attribute| attribute.path().is_ident("builder"))
+            .map(|attribute| res.parse_attribute(attribute))
+            .collect::<Vec<_>>();
+        crate::collect_results(results)?;
+        Ok(res)
+    }
+
+    fn parse_attribute(&mut self, attribute: &Attribute) -> syn::Result<()> {
         if let syn::AttrStyle::Inner(bang) = &attribute.style {
             return Err(Error::new(
                 attribute.span(),
                 "`builder` attribute only accepts outer one (the one without `!`)",
             ));
         }
-        let syn::Meta::List(meta_list) = attribute.meta else {
-            return Err(err());
-        };
-        let syn::MacroDelimiter::Paren(paren_token) = meta_list.delimiter else {
-            return Err(err());
-        };
-        let builder = meta_list.path.get_ident().cloned().ok_or_else(err)?;
-        if builder != "builder" {
-            return Err(err());
-        }
-        let FieldAttrInner {
-            key: each_ident,
-            eq: each_eq,
-            val: each_val,
-        } = syn::parse2(meta_list.tokens)?;
-        if each_ident != "each" {
-            return Err(err());
-        }
-        let each_val = syn::parse_str(&each_val.value())?;
-        Ok(Self {
-            pound_token: attribute.pound_token,
-            bracket_token: attribute.bracket_token,
-            builder,
-            paren_token,
-            each_ident,
-            each_eq,
-            each_val,
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                if self.each.is_some() {
+                    return Err(meta.error("duplicate `each` option"));
+                }
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.each = Some(val.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("default") {
+                if self.default.is_some() {
+                    return Err(meta.error("duplicate `default` option"));
+                }
+                self.default = if meta.input.peek(syn::Token![=]) {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    Some(FieldDefault::Expr(val.parse()?))
+                } else {
+                    Some(FieldDefault::Trait(meta.path.span()))
+                };
+                return Ok(());
+            }
+            let fallible = meta.path.is_ident("try_default_with");
+            if fallible || meta.path.is_ident("default_with") {
+                if self.default.is_some() {
+                    return Err(meta.error("duplicate `default` option"));
+                }
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.default = Some(FieldDefault::Async {
+                    path: val.parse()?,
+                    fallible,
+                });
+                return Ok(());
+            }
+            if meta.path.is_ident("setter") {
+                return self.setter.parse_meta(meta);
+            }
+            if meta.path.is_ident("skip") {
+                self.skip = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("sub_builder") {
+                let sub_builder = self.sub_builder.get_or_insert_with(Default::default);
+                if meta.input.peek(syn::Token![=]) {
+                    let val: syn::LitStr = meta.value()?.parse()?;
+                    sub_builder.builder = Some(val.parse()?);
+                } else if meta.input.peek(syn::token::Paren) {
+                    sub_builder.parse_meta(meta)?;
+                }
+                return Ok(());
+            }
+            if meta.path.is_ident("kind") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                if !matches!(val.value().as_str(), "option" | "vec" | "raw") {
+                    return Err(Error::new(
+                        val.span(),
+                        r#"expected `"option"`, `"vec"` or `"raw"`"#,
+                    ));
+                }
+                self.kind = Some(val);
+                return Ok(());
+            }
+            if meta.path.is_ident("merge") {
+                let val: syn::LitStr = meta.value()?.parse()?;
+                self.merge = MergeStrategy::parse(&val)?;
+                return Ok(());
+            }
+            Err(Error::new_spanned(
+                &attribute.meta,
+                r#"expected `builder(each = "...")`"#,
+            ))
         })
     }
 }
 
-impl syn::parse::Parse for FieldAttrInner {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let key: Ident = input.parse()?;
-        let eq: syn::Token![=] = input.parse()?;
-        let val: syn::LitStr = input.parse()?;
-        Ok(Self { key, eq, val })
+/// Name of the standard type which `ty` may refer to, see
+/// [`FieldTypeKind::parse`].
+fn std_type_name(ty: &Type) -> Option<&Ident> {
+    let Type::Path(syn::TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let mut segments = path.segments.iter().rev();
+    let last = segments.next()?;
+    let mut modules = Vec::new();
+    for seg in segments.rev() {
+        if !seg.arguments.is_empty() {
+            return None;
+        }
+        modules.push(seg.ident.to_string());
     }
+    let modules = modules.iter().map(String::as_str).collect::<Vec<_>>();
+    let std = match (last.ident.to_string().as_str(), modules.as_slice()) {
+        (_, []) => path.leading_colon.is_none(),
+        ("Option", ["std" | "core", "option"]) => true,
+        ("Vec", ["std" | "alloc", "vec"]) => true,
+        (_, ["std" | "alloc", "collections"]) => true,
+        (_, ["std" | "alloc", "collections", _]) => true,
+        _ => false,
+    };
+    std.then_some(&last.ident)
+}
+
+/// Type arguments given to the last segment of the path `ty`.
+fn type_args(ty: &Type) -> Vec<&Type> {
+    let Type::Path(syn::TypePath { path, .. }) = ty else {
+        return Vec::new();
+    };
+    let Some(syn::PathArguments::AngleBracketed(args)) =
+        path.segments.last().map(|seg| &seg.arguments)
+    else {
+        return Vec::new();
+    };
+    args.args
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Splits `Wrapper<T>` into the path `Wrapper`, its angle brackets and `T`.
+fn single_type_arg(ty: &Type) -> Option<(syn::Path, syn::Token![<], Type, syn::Token![>])> {
+    let Type::Path(syn::TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let mut path = path.clone();
+    let last = path.segments.last_mut()?;
+    let syn::PathArguments::AngleBracketed(args) =
+        std::mem::replace(&mut last.arguments, syn::PathArguments::None)
+    else {
+        return None;
+    };
+    let arg = match (args.args.len(), args.args.first()) {
+        (1, Some(syn::GenericArgument::Type(arg))) => arg.clone(),
+        _ => return None,
+    };
+    Some((path, args.lt_token, arg, args.gt_token))
+}
+
+/// Name of a method made of `prefix` and the name of a field, such as
+/// `get_name` or `clear_0` for the positional `_0`, which would otherwise be
+/// `non_snake_case`.
+fn prefixed_ident(prefix: &str, ident: &Ident) -> Ident {
+    let name = ident.unraw().to_string();
+    format_ident!("{}_{}", prefix, name.trim_start_matches('_'))
+}
+
+/// Builder of the type `ty` by the default naming, `a::Inner<T>` being built
+/// by `a::InnerBuilder<T>`.
+fn sub_builder_ty(ty: &Type) -> Option<Type> {
+    let Type::Path(syn::TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let mut path = path.clone();
+    let last = path.segments.last_mut()?;
+    last.ident = format_ident!("{}Builder", last.ident);
+    Some(Type::Path(syn::TypePath { qself: None, path }))
 }
@@ -1,13 +1,17 @@
+mod container;
 mod field;
+mod typestate;
 
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Fields};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident};
 
-use field::{Field, FieldTypeKind};
+use container::{BuilderPattern, ContainerAttribute};
+use field::{Field, FieldDefault, FieldTypeKind, MergeStrategy};
 
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
@@ -18,129 +22,735 @@ pub fn derive(input: TokenStream) -> TokenStream {
     }
 }
 
+/// The struct, or a variant of the enum, which a builder is generated for.
+pub(crate) struct Target<'a> {
+    pub input: &'a DeriveInput,
+    /// Path to construct the target with, `Struct` or `Enum::Variant`.
+    pub path: proc_macro2::TokenStream,
+    pub is_variant: bool,
+    pub container: ContainerAttribute,
+    pub fields: Vec<Field>,
+    pub builder_ident: Ident,
+    pub error_ident: Ident,
+    pub constructor: Ident,
+    pub build_fn: Ident,
+}
+
 fn derive_builder(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
-    let DeriveInput {
-        attrs: _,
-        vis: _,
-        ident,
-        generics: _,
-        data,
-    } = &input;
-    let builder_ident = format_ident!("{}Builder", ident);
-
-    let Data::Struct(syn::DataStruct {
-        fields: Fields::Named(fields),
-        ..
-    }) = data
-    else {
-        return Err(syn::Error::new(
-            input.span(),
-            "`derive(Builder)` only accepts struct with named field",
-        ));
+    let targets = match &input.data {
+        Data::Struct(data) => vec![Target::parse(&input, None, &data.fields)?],
+        Data::Enum(data) => {
+            let container = ContainerAttribute::parse_attributes(&input.attrs)?;
+            if container.name.is_some() || container.constructor.is_some() {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "`name` and `constructor` of an enum must be given on each variant",
+                ));
+            }
+            let targets = data
+                .variants
+                .iter()
+                .filter(|variant| !matches!(variant.fields, Fields::Unit))
+                .map(|variant| Target::parse(&input, Some(variant), &variant.fields));
+            collect_results(targets)?
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "`derive(Builder)` only accepts struct or enum",
+            ))
+        }
     };
-    let named_fields = fields
-        .named
-        .iter()
-        .cloned()
-        .map(Field::parse_field)
-        .collect::<syn::Result<Vec<_>>>()?;
-    let builder_fields = named_fields.iter().map(|Field { ident, ty, .. }| match ty {
-        FieldTypeKind::OptionWrapped { ty: oty, .. } => quote! {
-            pub #ident: ::std::option::Option<#oty>
-        },
-        FieldTypeKind::VecWrapped { ty: vty, .. } => quote! {
-            pub #ident: ::std::vec::Vec<#vty>
-        },
-        FieldTypeKind::Raw(rty) => quote! {
-            pub #ident: ::std::option::Option<#rty>
-        },
+    let code = targets.iter().map(|target| {
+        if target.container.typestate {
+            typestate::derive_typestate_builder(target)
+        } else {
+            derive_default_builder(target)
+        }
     });
+    let code = collect_results(code)?;
+    Ok(quote! {
+        #(#code)*
+    })
+}
 
-    let builder_methods = named_fields.iter().map(|Field { ident, ty, .. }| {
-        let method = match ty {
-            FieldTypeKind::OptionWrapped { ty: oty, .. } => quote! {
-                pub fn #ident(&mut self, #ident: #oty) -> &mut Self {
-                    self.#ident = ::std::option::Option::Some(#ident);
-                    self
+impl<'a> Target<'a> {
+    fn parse(
+        input: &'a DeriveInput,
+        variant: Option<&syn::Variant>,
+        fields: &Fields,
+    ) -> syn::Result<Self> {
+        let ident = &input.ident;
+        let attrs = match variant {
+            Some(variant) => input.attrs.iter().chain(&variant.attrs).cloned().collect(),
+            None => input.attrs.clone(),
+        };
+        let container = ContainerAttribute::parse_attributes(&attrs);
+        let fields = fields
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, field)| Field::parse_field(field, index));
+        let (container, mut fields) = combine_errors(container, collect_results(fields))?;
+        for field in &mut fields {
+            field.attrs.setter.into |= container.setter.into;
+        }
+        let append = fields.iter().find_map(|field| match field.attrs.merge {
+            MergeStrategy::Append(span) => Some(span),
+            MergeStrategy::Replace => None,
+        });
+        if let (false, Some(span)) = (container.merge, append) {
+            return Err(syn::Error::new(
+                span,
+                r#"`#[builder(merge = "append")]` needs `#[builder(merge)]` on the builder"#,
+            ));
+        }
+        let unsupported = fields
+            .iter()
+            .find_map(|field| match (&field.attrs.default, &field.ty) {
+                (Some(default @ FieldDefault::Async { path, .. }), _) => {
+                    Some((path.span(), default.option_name()))
                 }
-            },
-            FieldTypeKind::VecWrapped { ty: vty, .. } => quote! {
-                pub fn #ident(&mut self, #ident: ::std::vec::Vec<#vty>) -> &mut Self {
-                    self.#ident = #ident;
-                    self
+                (_, FieldTypeKind::SubBuilder { .. }) => Some((field.span, "sub_builder")),
+                _ => None,
+            });
+        if let (true, Some((span, option))) = (container.typestate, unsupported) {
+            return Err(syn::Error::new(
+                span,
+                format!("`#[builder(typestate)]` does not support `{}`", option),
+            ));
+        }
+        // the builder of the field is handed out by `&mut` and taken out of
+        // the builder by `build`
+        let sub_builder = fields
+            .iter()
+            .find(|field| matches!(field.ty, FieldTypeKind::SubBuilder { .. }));
+        if let (false, Some(field)) = (container.pattern == BuilderPattern::Mutable, sub_builder) {
+            return Err(syn::Error::new(
+                field.ident.span(),
+                r#"`#[builder(sub_builder)]` only supports `pattern = "mutable"`"#,
+            ));
+        }
+        let (path, builder_ident, constructor) = match variant {
+            Some(syn::Variant {
+                ident: variant_ident,
+                ..
+            }) => (
+                quote!(#ident::#variant_ident),
+                format_ident!("{}{}Builder", ident, variant_ident),
+                format_ident!("{}_builder", snake_case(variant_ident)),
+            ),
+            None => (
+                quote!(#ident),
+                format_ident!("{}Builder", ident),
+                format_ident!("builder"),
+            ),
+        };
+        let builder_ident = container.name.clone().unwrap_or(builder_ident);
+        let error_ident = format_ident!("{}Error", builder_ident);
+        let constructor = container.constructor.clone().unwrap_or(constructor);
+        let build_fn = container
+            .build_fn
+            .clone()
+            .unwrap_or_else(|| format_ident!("build"));
+        Ok(Self {
+            input,
+            path,
+            is_variant: variant.is_some(),
+            container,
+            fields,
+            builder_ident,
+            error_ident,
+            constructor,
+            build_fn,
+        })
+    }
+}
+
+impl Target<'_> {
+    /// Fields stored in the builder, that is all of them but the skipped ones.
+    pub fn stored_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields.iter().filter(|field| !field.attrs.skip)
+    }
+
+    /// Constructs the target following the `stored` statements, which bind the
+    /// value of every stored field to a local named after its builder field.
+    /// Skipped fields are computed after that, so their defaults may refer to
+    /// the other fields by name. Deprecated fields are only deprecated for the
+    /// user, whose setters carry the `#[deprecated]` instead.
+    pub fn construct(
+        &self,
+        stored: impl Iterator<Item = proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
+        let skipped = self.fields.iter().filter(|field| field.attrs.skip).map(
+            |Field { ident, attrs, .. }| {
+                let default = attrs.default.as_ref().map_or_else(
+                    || quote!(::core::default::Default::default()),
+                    FieldDefault::to_expr,
+                );
+                quote! {
+                    let #ident = #default;
                 }
             },
-            FieldTypeKind::Raw(rty) => quote! {
-                pub fn #ident(&mut self, #ident: #rty) -> &mut Self {
-                    self.#ident = ::std::option::Option::Some(#ident);
-                    self
+        );
+        let members = self
+            .fields
+            .iter()
+            .map(|Field { ident, member, .. }| quote!(#member: #ident));
+        let path = &self.path;
+        quote! {
+            {
+                #(#stored)*
+                #(#skipped)*
+                #[allow(deprecated)]
+                let __value = #path {
+                    #(#members),*
+                };
+                __value
+            }
+        }
+    }
+
+    /// Doc comments of the builder and of `build`, the latter saying that it
+    /// is `conditional` on the required fields being set, such as "failing
+    /// unless".
+    pub fn docs(&self, conditional: &str) -> (String, String) {
+        let target = self.path.to_string().replace(' ', "");
+        let required = self
+            .fields
+            .iter()
+            .filter(|field| field.is_required())
+            .map(Field::doc_name)
+            .collect::<Vec<_>>();
+        if required.is_empty() {
+            return (
+                format!("Builder of [`{}`], whose fields are all optional.", target),
+                format!("Builds the [`{}`].", target),
+            );
+        }
+        let verb = if required.len() == 1 { "is" } else { "are" };
+        let required = required.join(", ");
+        (
+            format!(
+                "Builder of [`{}`], which needs {} to be set.",
+                target, required,
+            ),
+            format!(
+                "Builds the [`{}`], {} {} {} set.",
+                target, conditional, required, verb,
+            ),
+        )
+    }
+
+    /// Generates `Struct::to_builder` for the builder type `builder_ty`,
+    /// along with `From<Struct>` under `#[builder(from)]`, given the
+    /// initializers of the builder fields which may refer to the stored fields
+    /// of the struct by name. Skipped fields are dropped, as `build` computes
+    /// them again.
+    pub fn to_builder_impl(
+        &self,
+        builder_ty: proc_macro2::TokenStream,
+        builder_fields: impl Iterator<Item = proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
+        let DeriveInput {
+            ident, generics, ..
+        } = self.input;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let path = &self.path;
+        let builder_ident = &self.builder_ident;
+        let builder_fields = builder_fields.collect::<Vec<_>>();
+        let members = self
+            .stored_fields()
+            .map(|Field { ident, member, .. }| match member {
+                syn::Member::Named(_) => quote!(#ident),
+                syn::Member::Unnamed(_) => quote!(#member: #ident),
+            })
+            .collect::<Vec<_>>();
+        let idents = self.stored_fields().map(|field| &field.ident);
+        let clone_bounds = self.stored_fields().map(|field| {
+            let ty = field.ty.setter_ty();
+            quote!(for<'__a> #ty: ::core::clone::Clone)
+        });
+        // the builder of a sub-builder field may be written by hand, without
+        // `From` for the value
+        let sub_builder_bounds = self
+            .stored_fields()
+            .filter_map(|field| match &field.ty {
+                FieldTypeKind::SubBuilder { ty, builder, .. } => {
+                    Some(quote!(for<'__a> #builder: ::core::convert::From<#ty>))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let from_impl = self.container.from.then(|| {
+            let mut generics = generics.clone();
+            let predicates = &mut generics.make_where_clause().predicates;
+            for bound in &sub_builder_bounds {
+                predicates.push(syn::parse_quote!(#bound));
+            }
+            let where_clause = &generics.where_clause;
+            quote! {
+                impl #impl_generics ::core::convert::From<#ident #ty_generics> for #builder_ty #where_clause {
+                    #[allow(deprecated)]
+                    fn from(value: #ident #ty_generics) -> Self {
+                        let #path { #(#members,)* .. } = value;
+                        Self {
+                            #(#builder_fields,)*
+                        }
+                    }
+                }
+            }
+        });
+        quote! {
+            #from_impl
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                // the fields are cloned one by one rather than the whole
+                // value, which could not be moved out of if it implemented
+                // `Drop`; the higher-ranked bounds keep them from being
+                // rejected as trivially false
+                /// Creates a builder with every field set to a clone of the
+                /// one in this value.
+                #[allow(deprecated)]
+                pub fn to_builder(&self) -> #builder_ty
+                where
+                    #(#clone_bounds,)*
+                    #(#sub_builder_bounds,)*
+                {
+                    let #path { #(#members,)* .. } = self;
+                    #(let #idents = ::core::clone::Clone::clone(#idents);)*
+                    let __builder: #builder_ty = #builder_ident {
+                        #(#builder_fields,)*
+                    };
+                    __builder
+                }
+            }
+        }
+    }
+}
+
+fn derive_default_builder(target: &Target) -> syn::Result<proc_macro2::TokenStream> {
+    let Target {
+        input: DeriveInput {
+            ident, generics, ..
+        },
+        is_variant,
+        container,
+        builder_ident,
+        error_ident,
+        constructor,
+        build_fn,
+        ..
+    } = target;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let builder_fields = target.stored_fields().map(|Field { ident, ty, .. }| {
+        let storage_ty = ty.storage_ty();
+        quote! {
+            #ident: #storage_ty
+        }
+    });
+    // variants do not necessarily use every generic parameter of the enum,
+    // nor do the stored fields of a struct with skipped fields
+    let has_phantom = *is_variant || !generics.params.is_empty();
+    let phantom_attrs = container.phantom_attrs();
+    let phantom_field = has_phantom.then(|| {
+        quote! {
+            #phantom_attrs
+            __phantom: ::core::marker::PhantomData<#ident #ty_generics>,
+        }
+    });
+    let phantom_default = has_phantom.then(|| {
+        quote! {
+            __phantom: ::core::marker::PhantomData,
+        }
+    });
+
+    let pattern = container.pattern;
+    let builder_methods = target
+        .stored_fields()
+        .flat_map(|field| field.builder_methods(pattern));
+    let getter_methods = container
+        .getters
+        .then(|| target.stored_fields().flat_map(Field::getter_methods))
+        .into_iter()
+        .flatten();
+
+    // `build` cannot await the `default_with` functions, so it considers those
+    // fields missing
+    let build_method_fields = |asynchronous: bool| {
+        target.stored_fields().map(
+            move |Field {
+                      ident,
+                      member,
+                      ty,
+                      attrs,
+                      ..
+                  }| {
+                let value = match (pattern, ty) {
+                    (
+                        BuilderPattern::Mutable,
+                        FieldTypeKind::VecWrapped { .. }
+                        | FieldTypeKind::Collection { .. }
+                        | FieldTypeKind::SubBuilder { .. },
+                    ) => quote!(::core::mem::take(&mut self.#ident)),
+                    (BuilderPattern::Mutable, _) => quote!(self.#ident.take()),
+                    (BuilderPattern::Owned, _) => quote!(self.#ident),
+                    (BuilderPattern::Immutable, _) => {
+                        quote!(::core::clone::Clone::clone(&self.#ident))
+                    }
+                };
+                match (ty, &attrs.default) {
+                    (
+                        FieldTypeKind::OptionWrapped { .. }
+                        | FieldTypeKind::VecWrapped { .. }
+                        | FieldTypeKind::Collection { .. },
+                        _,
+                    ) => {
+                        quote! {
+                            let #ident = #value;
+                        }
+                    }
+                    // the builder of the field may take itself by reference
+                    (FieldTypeKind::SubBuilder { build_fn, .. }, _) => {
+                        let error = container.boxed_error(quote!(e));
+                        quote! {
+                            let #ident = {
+                                #[allow(unused_mut)]
+                                let mut __builder = #value;
+                                __builder.#build_fn()
+                            }
+                            .map_err(|e| #error_ident::SubBuilder(stringify!(#member), #error))?;
+                        }
+                    }
+                    (
+                        FieldTypeKind::Raw(_),
+                        Some(default @ FieldDefault::Async { fallible, .. }),
+                    ) if asynchronous => {
+                        let mut default = default.to_expr();
+                        if *fallible {
+                            let error = container.boxed_error(quote!(e));
+                            default = quote! {
+                                #default.map_err(|e| {
+                                    #error_ident::DefaultFailed(stringify!(#member), #error)
+                                })?
+                            };
+                        }
+                        quote! {
+                            let #ident = match #value {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => #default,
+                            };
+                        }
+                    }
+                    (FieldTypeKind::Raw(_), Some(FieldDefault::Async { .. }) | None) => quote! {
+                        let #ident = #value
+                            .ok_or(#error_ident::MissingField(stringify!(#member)))?;
+                    },
+                    (FieldTypeKind::Raw(_), Some(default)) => {
+                        let default = default.to_expr();
+                        quote! {
+                            let #ident = #value.unwrap_or_else(|| #default);
+                        }
+                    }
                 }
             },
-        };
-        (ident, method)
+        )
+    };
+
+    let default_fields = target.stored_fields().map(|Field { ident, .. }| {
+        quote! {
+            #ident: ::core::default::Default::default()
+        }
     });
-    let builder_each_methods = named_fields.iter().filter_map(|field| {
-        let Field {
-            ident, ty, attrs, ..
-        } = field;
-        let field::FieldAttribute { each_val, .. } = attrs.as_ref()?;
-        let FieldTypeKind::VecWrapped { ty, .. } = ty else {
-            panic!(r#"`#[builder(each = "...")]` can only be used for `Vec<T>`"#);
-        };
-        let method = quote! {
-            pub fn #each_val(&mut self, #each_val: #ty) -> &mut Self {
-                self.#ident.push(#each_val);
-                self
+
+    // a value of the enum may be any of its variants
+    let from_impl = (!is_variant).then(|| {
+        let from_fields = target.stored_fields().map(|Field { ident, ty, .. }| {
+            let value = ty.to_storage(ident);
+            quote! {
+                #ident: #value
             }
-        };
-        Some((each_val, method))
+        });
+        let from_fields = from_fields.chain(has_phantom.then(|| {
+            quote! {
+                __phantom: ::core::marker::PhantomData
+            }
+        }));
+        target.to_builder_impl(quote!(#builder_ident #ty_generics), from_fields)
     });
-    let builder_methods = builder_methods
-        .chain(builder_each_methods)
-        .collect::<HashMap<_, _>>();
-    let builder_methods = builder_methods.into_values();
-
-    let build_method_fields = named_fields.iter().map(|Field { ident, ty, .. }| match ty {
-        FieldTypeKind::OptionWrapped { .. } => quote! {
-            #ident: self.#ident.take()
-        },
-        FieldTypeKind::VecWrapped { .. } => quote! {
-            #ident: {
-                let v = self.#ident.clone();
-                self.#ident = vec![];
-                v
+
+    let merge_methods = container.merge.then(|| {
+        let (merge_bounds, merge_fields): (Vec<_>, Vec<_>) =
+            target.stored_fields().map(Field::merge_field).unzip();
+        let merge_bounds = merge_bounds.into_iter().flatten().collect::<Vec<_>>();
+        quote! {
+            /// Overlays the fields set in `other` onto this builder.
+            pub fn merge(&mut self, other: Self)
+            where
+                #(#merge_bounds,)*
+            {
+                #(#merge_fields)*
             }
-        },
-        FieldTypeKind::Raw(_) => quote! {
-            #ident: self.#ident.take()
-                .ok_or(concat!("field ", stringify!(#ident), " is not set").to_string())?
-        },
+
+            /// Fills in the fields not set in this builder from `fallback`.
+            pub fn or(self, fallback: Self) -> Self
+            where
+                #(#merge_bounds,)*
+            {
+                let mut __merged = fallback;
+                __merged.merge(self);
+                __merged
+            }
+        }
     });
 
-    let code = quote! {
-        #[derive(Default)]
-        struct #builder_ident {
-            #(#builder_fields),*
+    let build_receiver = pattern.build_receiver();
+    let builder_attrs = container.builder_attrs();
+
+    let build_body =
+        container.build_body(error_ident, target.construct(build_method_fields(false)));
+    let has_async_default = target
+        .stored_fields()
+        .any(|field| matches!(field.attrs.default, Some(FieldDefault::Async { .. })));
+    let build_async_method = has_async_default.then(|| {
+        let build_body =
+            container.build_body(error_ident, target.construct(build_method_fields(true)));
+        quote! {
+            /// Builds the value like `build`, awaiting the `default_with` and
+            /// `try_default_with` functions of the fields which were not set.
+            pub async fn build_async(
+                #build_receiver
+            ) -> ::core::result::Result<#ident #ty_generics, #error_ident> {
+                #build_body
+            }
+        }
+    });
+    let builder_error = derive_builder_error(target);
+    let vis = &container.vis;
+    let (builder_doc, build_doc) = target.docs("failing with `MissingField` unless");
+
+    let mut reserved = vec![build_fn.clone()];
+    if container.merge {
+        reserved.extend([format_ident!("merge"), format_ident!("or")]);
+    }
+    reserved.extend(has_async_default.then(|| format_ident!("build_async")));
+    let builder_methods = unique_methods(builder_methods.chain(getter_methods), &reserved)?;
+
+    Ok(quote! {
+        #builder_error
+
+        #[doc = #builder_doc]
+        #builder_attrs
+        #vis struct #builder_ident #generics #where_clause {
+            #(#builder_fields,)*
+            #phantom_field
         }
 
-        impl #builder_ident {
+        impl #impl_generics ::core::default::Default for #builder_ident #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#default_fields,)*
+                    #phantom_default
+                }
+            }
+        }
+
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
             #(#builder_methods)*
 
-            pub fn build(&mut self) -> ::std::result::Result<
-                #ident,
-                ::std::boxed::Box<dyn ::std::error::Error>
-            > {
-                ::std::result::Result::Ok(#ident {
-                    #(#build_method_fields),*
-                })
+            #merge_methods
+
+            #[doc = #build_doc]
+            pub fn #build_fn(#build_receiver) -> ::core::result::Result<#ident #ty_generics, #error_ident> {
+                #build_body
             }
+
+            #build_async_method
         }
 
-        impl #ident {
-            pub fn builder() -> #builder_ident {
-                ::std::default::Default::default()
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Creates a builder with none of the fields set.
+            pub fn #constructor() -> #builder_ident #ty_generics {
+                ::core::default::Default::default()
             }
         }
+
+        #from_impl
+    })
+}
+
+/// The error type returned by `build`, which has a `DefaultFailed` variant
+/// for the errors of `try_default_with` functions and a `SubBuilder` variant
+/// for the errors of the builders of fields, if the target has such fields.
+fn derive_builder_error(target: &Target) -> proc_macro2::TokenStream {
+    let Target {
+        container,
+        error_ident,
+        build_fn,
+        ..
+    } = target;
+    let vis = &container.vis;
+    let alloc = container.alloc_crate();
+    let async_default = target
+        .stored_fields()
+        .any(|field| matches!(field.attrs.default, Some(FieldDefault::Async { .. })));
+    let doc = if async_default {
+        format!(
+            "Error returned by `{}` and `build_async` when the value cannot be built.",
+            build_fn,
+        )
+    } else {
+        format!(
+            "Error returned by `{}` when the value cannot be built.",
+            build_fn
+        )
     };
-    Ok(code)
+    let fallible_default = target.stored_fields().any(|field| {
+        matches!(
+            field.attrs.default,
+            Some(FieldDefault::Async { fallible: true, .. })
+        )
+    });
+    let sub_builder = target
+        .stored_fields()
+        .any(|field| matches!(field.ty, FieldTypeKind::SubBuilder { .. }));
+    // variants naming a field along with the error it ran into
+    let field_errors = [
+        (
+            fallible_default,
+            format_ident!("DefaultFailed"),
+            "The `try_default_with` function of a field which was not set failed.",
+            "default of field {} failed: {}",
+        ),
+        (
+            sub_builder,
+            format_ident!("SubBuilder"),
+            "The builder of a field failed to build it.",
+            "{}: {}",
+        ),
+    ];
+    let field_errors = field_errors
+        .into_iter()
+        .filter(|(present, ..)| *present)
+        .map(|(_, variant, doc, message)| (variant, doc, message))
+        .collect::<Vec<_>>();
+    let error_ty = container.boxed_error_ty();
+    let variants = field_errors.iter().map(|(variant, doc, _)| {
+        quote! {
+            #[doc = #doc]
+            #variant(&'static str, #error_ty),
+        }
+    });
+    let display_arms = field_errors.iter().map(|(variant, _, message)| {
+        quote! {
+            Self::#variant(field, error) => write!(f, #message, field, error),
+        }
+    });
+    // `core::error::Error` is not stable yet
+    let impl_error = (!container.no_std).then(|| {
+        let source = (!field_errors.is_empty()).then(|| {
+            let variants = field_errors.iter().map(|(variant, ..)| variant);
+            quote! {
+                fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                    match self {
+                        #(Self::#variants(_, error) => ::core::option::Option::Some(&**error),)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+        });
+        quote! {
+            impl ::std::error::Error for #error_ident {
+                #source
+            }
+        }
+    });
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug)]
+        #vis enum #error_ident {
+            /// A required field was not set.
+            MissingField(&'static str),
+            /// The validation hook rejected the built value.
+            Validation(#alloc::string::String),
+            #(#variants)*
+        }
+
+        impl ::core::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    Self::MissingField(field) => write!(f, "field {} is not set", field),
+                    Self::Validation(message) => f.write_str(message),
+                    #(#display_arms)*
+                }
+            }
+        }
+
+        #impl_error
+    }
+}
+
+/// Checks that none of the builder methods share a name with each other or
+/// with the `reserved` ones, and returns them in order.
+fn unique_methods(
+    methods: impl IntoIterator<Item = (Ident, proc_macro2::TokenStream)>,
+    reserved: &[Ident],
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut names = reserved.iter().cloned().collect::<HashSet<_>>();
+    let methods = methods.into_iter().map(|(name, method)| {
+        if names.insert(name.clone()) {
+            Ok(method)
+        } else {
+            Err(syn::Error::new(
+                name.span(),
+                format!("the builder already has a method named `{}`", name),
+            ))
+        }
+    });
+    collect_results(methods)
+}
+
+/// Collects the successful results, or combines all of the errors into one.
+fn collect_results<T>(results: impl IntoIterator<Item = syn::Result<T>>) -> syn::Result<Vec<T>> {
+    let mut values = Vec::new();
+    let mut error: Option<syn::Error> = None;
+    for result in results {
+        match (result, &mut error) {
+            (Ok(value), _) => values.push(value),
+            (Err(e), Some(error)) => error.combine(e),
+            (Err(e), None) => error = Some(e),
+        }
+    }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(values),
+    }
+}
+
+fn combine_errors<T, U>(a: syn::Result<T>, b: syn::Result<U>) -> syn::Result<(T, U)> {
+    match (a, b) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        (Err(e), Ok(_)) | (Ok(_), Err(e)) => Err(e),
+        (Err(mut e), Err(other)) => {
+            e.combine(other);
+            Err(e)
+        }
+    }
+}
+
+/// Converts `CamelCase` into `snake_case`.
+fn snake_case(ident: &Ident) -> String {
+    let mut snake = String::new();
+    for (i, c) in ident.unraw().to_string().char_indices() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
 }
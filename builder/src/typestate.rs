@@ -0,0 +1,247 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, GenericParam, Generics, Ident};
+
+use crate::container::BuilderPattern;
+use crate::field::{Field, FieldTypeKind};
+use crate::Target;
+
+/// Generates a builder which tracks the required fields (see
+/// [`Field::is_required`]) in its type parameters.
+///
+/// Every required field gets a state parameter that is `()` while the field is
+/// unset and `(T,)` once it has been set, so that `build` is only implemented
+/// for the builder type whose required fields are all set. Since the types of
+/// the required fields are swapped out for their states, the builder holds a
+/// `PhantomData` of the input struct to keep all of its generic parameters in
+/// use.
+///
+/// `build` returns the struct itself, unless a validation hook is given in
+/// which case it returns a `Result` just like the default builder does.
+pub fn derive_typestate_builder(target: &Target) -> syn::Result<TokenStream> {
+    let Target {
+        input: DeriveInput {
+            ident, generics, ..
+        },
+        container,
+        builder_ident,
+        error_ident,
+        constructor,
+        build_fn,
+        ..
+    } = target;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let states = target
+        .stored_fields()
+        .filter(|f| f.is_required())
+        .enumerate()
+        // named by position, as names derived from the fields may collide
+        .map(|(index, Field { ident, .. })| (ident, format_ident!("__State{}", index)))
+        .collect::<Vec<_>>();
+    let state_of = |field: &Ident| {
+        states
+            .iter()
+            .find_map(|(ident, state)| (*ident == field).then_some(state))
+    };
+
+    let mut builder_generics = generics.clone();
+    builder_generics.params.extend(
+        states
+            .iter()
+            .map(|(_, state)| -> GenericParam { syn::parse_quote!(#state = ()) }),
+    );
+    let (builder_impl_generics, builder_ty_generics, _) = builder_generics.split_for_impl();
+    let user_args = generic_args(generics);
+
+    let builder_fields = target.stored_fields().map(|Field { ident, ty, .. }| {
+        if let Some(state) = state_of(ident) {
+            return quote! {
+                #ident: #state
+            };
+        }
+        let storage_ty = ty.storage_ty();
+        quote! {
+            #ident: #storage_ty
+        }
+    });
+
+    let builder_methods = target.stored_fields().flat_map(|field| {
+        let Field {
+            ident,
+            ty,
+            attrs,
+            forward_attrs,
+            ..
+        } = field;
+        let (FieldTypeKind::Raw(rty), true) = (ty, field.is_required()) else {
+            return field.builder_methods(BuilderPattern::Owned);
+        };
+        let setter_ident = field.setter_ident();
+        let (params, value) = attrs.setter.params(ident, rty);
+        let next_states = states.iter().map(|(other, state)| {
+            if *other == ident {
+                quote!((#rty,))
+            } else {
+                quote!(#state)
+            }
+        });
+        let moved_fields = target.stored_fields().map(|Field { ident: other, .. }| {
+            if other == ident {
+                quote!(#other: (#value,))
+            } else {
+                quote!(#other: self.#other)
+            }
+        });
+        let method = quote! {
+            #(#forward_attrs)*
+            pub fn #setter_ident(self, #params)
+                -> #builder_ident<#(#user_args,)* #(#next_states),*>
+            {
+                #builder_ident {
+                    #(#moved_fields,)*
+                    __phantom: ::core::marker::PhantomData,
+                }
+            }
+        };
+        vec![(setter_ident.clone(), method)]
+    });
+    let builder_methods = crate::unique_methods(builder_methods, &[build_fn.clone()])?;
+
+    let set_states = target
+        .stored_fields()
+        .filter(|f| f.is_required())
+        .map(|Field { ty, .. }| {
+            let ty = ty.as_inner();
+            quote!((#ty,))
+        })
+        .collect::<Vec<_>>();
+    let build_method_fields = target.stored_fields().map(|field| {
+        let ident = &field.ident;
+        if field.is_required() {
+            return quote! {
+                let #ident = self.#ident.0;
+            };
+        }
+        match &field.attrs.default {
+            Some(default) => {
+                let default = default.to_expr();
+                quote! {
+                    let #ident = self.#ident.unwrap_or_else(|| #default);
+                }
+            }
+            None => quote! {
+                let #ident = self.#ident;
+            },
+        }
+    });
+    let unset_states = states.iter().map(|_| quote!(()));
+    let initial_fields = target.stored_fields().map(|field| {
+        let ident = &field.ident;
+        if field.is_required() {
+            quote! {
+                #ident: ()
+            }
+        } else {
+            quote! {
+                #ident: ::core::default::Default::default()
+            }
+        }
+    });
+
+    // a value of the enum may be any of its variants
+    let from_impl = (!target.is_variant).then(|| {
+        let from_fields = target.stored_fields().map(|field| {
+            let Field { ident, ty, .. } = field;
+            if field.is_required() {
+                return quote! {
+                    #ident: (#ident,)
+                };
+            }
+            let value = ty.to_storage(ident);
+            quote! {
+                #ident: #value
+            }
+        });
+        let from_fields = from_fields.chain(Some(quote! {
+            __phantom: ::core::marker::PhantomData
+        }));
+        target.to_builder_impl(
+            quote!(#builder_ident<#(#user_args,)* #(#set_states),*>),
+            from_fields,
+        )
+    });
+
+    let vis = &container.vis;
+    let (builder_doc, build_doc) = target.docs("which is only available once");
+    let built = target.construct(build_method_fields);
+    let (builder_error, build_method) = if container.validate.is_some() {
+        let build_body = container.build_body(error_ident, built);
+        let build_method = quote! {
+            #[doc = #build_doc]
+            pub fn #build_fn(self) -> ::core::result::Result<#ident #ty_generics, #error_ident> {
+                #build_body
+            }
+        };
+        (Some(crate::derive_builder_error(target)), build_method)
+    } else {
+        let build_method = quote! {
+            #[doc = #build_doc]
+            pub fn #build_fn(self) -> #ident #ty_generics {
+                #built
+            }
+        };
+        (None, build_method)
+    };
+
+    Ok(quote! {
+        #builder_error
+
+        #[doc = #builder_doc]
+        #vis struct #builder_ident #builder_generics #where_clause {
+            #(#builder_fields,)*
+            __phantom: ::core::marker::PhantomData<#ident #ty_generics>,
+        }
+
+        impl #builder_impl_generics #builder_ident #builder_ty_generics #where_clause {
+            #(#builder_methods)*
+        }
+
+        impl #impl_generics #builder_ident<#(#user_args,)* #(#set_states),*> #where_clause {
+            #build_method
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Creates a builder with none of the fields set.
+            pub fn #constructor() -> #builder_ident<#(#user_args,)* #(#unset_states),*> {
+                #builder_ident {
+                    #(#initial_fields,)*
+                    __phantom: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        #from_impl
+    })
+}
+
+fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(l) => {
+                let lifetime = &l.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Type(t) => {
+                let ident = &t.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,46 @@
+// Generic structs should be able to derive Builder too. The generated builder
+// carries over every type, lifetime and const parameter of the input struct
+// along with its where-clause, so that
+//
+//     #[derive(Builder)]
+//     pub struct Request<'a, T: Clone, const N: usize> where T: Debug { ... }
+//
+// produces a `RequestBuilder<'a, T, N>` and an `impl<'a, T: Clone, const N:
+// usize> Request<'a, T, N>` holding the `builder()` constructor.
+//
+// Note that `#[derive(Default)]` on the builder would add a `T: Default` bound
+// that the input struct never asked for, so the Default impl of the builder is
+// written out by hand instead.
+
+use derive_builder::Builder;
+use std::fmt::Debug;
+
+#[derive(Builder)]
+pub struct Request<'a, T: Clone, const N: usize>
+where
+    T: Debug,
+{
+    path: &'a str,
+    headers: [u8; N],
+    #[builder(each = "item")]
+    items: Vec<T>,
+    body: Option<T>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct NotDefault(u32);
+
+fn main() {
+    let request = Request::builder()
+        .path("/index.html")
+        .headers([0, 1])
+        .item(NotDefault(1))
+        .item(NotDefault(2))
+        .build()
+        .unwrap();
+
+    assert_eq!(request.path, "/index.html");
+    assert_eq!(request.headers, [0, 1]);
+    assert_eq!(request.items, vec![NotDefault(1), NotDefault(2)]);
+    assert_eq!(request.body, None);
+}
@@ -0,0 +1,44 @@
+// Rather than a `Box<dyn Error>` wrapping a formatted string, `build()` should
+// fail with a dedicated error enum generated next to the builder:
+//
+//     #[derive(Debug)]
+//     enum CommandBuilderError {
+//         MissingField(&'static str),
+//         Validation(String),
+//     }
+//
+// so that callers can match on which field was left unset. The enum still
+// implements Display and std::error::Error, which keeps `?` into a
+// `Box<dyn Error>` working as before.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    current_dir: String,
+}
+
+fn build_boxed() -> Result<Command, Box<dyn Error>> {
+    let command = Command::builder().executable("cargo".to_owned()).build()?;
+    Ok(command)
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+
+    match err {
+        CommandBuilderError::MissingField(field) => assert_eq!(field, "current_dir"),
+        CommandBuilderError::Validation(_) => unreachable!(),
+    }
+    assert_eq!(err.to_string(), "field current_dir is not set");
+
+    let err = build_boxed().err().unwrap();
+    assert_eq!(err.to_string(), "field current_dir is not set");
+}
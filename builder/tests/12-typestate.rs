@@ -0,0 +1,48 @@
+// With `#[builder(typestate)]` on the struct, the builder keeps track of which
+// required fields have been set in its type parameters. Each required field
+// adds a state parameter which is `()` until the setter is called and `(T,)`
+// afterwards, and `build()` is only implemented once every state parameter is
+// set. Forgetting a required field therefore turns into a compile error rather
+// than a `MissingField` error at runtime.
+//
+// The setters take the builder by value since each one of them may return a
+// builder of a different type. The state parameters are named by position, so
+// fields with similar names such as `foo_bar` and `fooBar` do not clash.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command<'a, T> {
+    executable: &'a str,
+    #[builder(each = "arg")]
+    args: Vec<T>,
+    current_dir: Option<String>,
+    r#type: u8,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+#[allow(non_snake_case)]
+pub struct Pair {
+    foo_bar: u8,
+    fooBar: u8,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build")
+        .executable("cargo")
+        .r#type(0)
+        .arg("--release")
+        .executable("cross")
+        .build();
+
+    assert_eq!(command.executable, "cross");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.r#type, 0);
+
+    let pair = Pair::builder().fooBar(2).foo_bar(1).build();
+    assert_eq!((pair.foo_bar, pair.fooBar), (1, 2));
+}
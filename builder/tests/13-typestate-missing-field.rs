@@ -0,0 +1,17 @@
+// A typestate builder which is missing one of its required fields has no
+// `build` method at all.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    current_dir: String,
+}
+
+fn main() {
+    let _command = Command::builder()
+        .executable("cargo".to_owned())
+        .build();
+}
@@ -0,0 +1,52 @@
+// A required field may be given a fallback value which `build()` uses when the
+// field was never set, instead of failing with `MissingField`.
+//
+//     #[builder(default)]                // Default::default()
+//     #[builder(default = "8080")]       // any expression
+//
+// The string in `default = "..."` is parsed as a Rust expression. In typestate
+// mode a field with a default is not required, so `build()` is available
+// without it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+    #[builder(default)]
+    workers: usize,
+    #[builder(default = r#"String::from("/")"#)]
+    root: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    host: String,
+    #[builder(default = "80")]
+    port: u16,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .workers(4)
+        .build()
+        .unwrap();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, 4);
+    assert_eq!(server.root, "/");
+
+    let client = Client::builder().host("localhost".to_owned()).build();
+    assert_eq!(client.port, 80);
+
+    let client = Client::builder()
+        .port(8000)
+        .host("localhost".to_owned())
+        .build();
+    assert_eq!(client.port, 8000);
+}
@@ -0,0 +1,58 @@
+// The way setters and `build()` receive the builder is chosen with a container
+// option:
+//
+//     #[builder(pattern = "mutable")]    // default: `&mut self` setters,
+//                                        // `build(&mut self)`
+//     #[builder(pattern = "owned")]      // `self` setters, `build(self)`
+//     #[builder(pattern = "immutable")]  // `&self` setters returning an
+//                                        // updated clone, `build(&self)`
+//
+// The owned builder moves its fields into the built struct, so neither the
+// field types nor the elements of Vec fields need to implement Clone.
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq)]
+pub struct Chunk(Vec<u8>);
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Upload {
+    name: String,
+    #[builder(each = "chunk")]
+    chunks: Vec<Chunk>,
+    first: Option<Chunk>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Request {
+    method: String,
+    #[builder(each = "header")]
+    headers: Vec<String>,
+}
+
+fn main() {
+    let upload = Upload::builder()
+        .name("data.bin".to_owned())
+        .chunk(Chunk(vec![0; 4]))
+        .chunk(Chunk(vec![1; 4]))
+        .first(Chunk(vec![]))
+        .build()
+        .unwrap();
+
+    assert_eq!(upload.name, "data.bin");
+    assert_eq!(upload.chunks, vec![Chunk(vec![0; 4]), Chunk(vec![1; 4])]);
+    assert_eq!(upload.first, Some(Chunk(vec![])));
+
+    let base = Request::builder().header("Accept: */*".to_owned());
+    let get = base.method("GET".to_owned());
+    let post = base.method("POST".to_owned());
+
+    assert!(base.build().is_err());
+    let get = get.build().unwrap();
+    let post = post.build().unwrap();
+    assert_eq!(get.method, "GET");
+    assert_eq!(post.method, "POST");
+    assert_eq!(post.headers, vec!["Accept: */*"]);
+}
@@ -0,0 +1,50 @@
+// `#[builder(setter(into))]` makes a setter accept anything convertible into
+// the field type, so callers can write `.executable("cargo")` instead of
+// `.executable("cargo".to_owned())`.
+//
+//     pub fn executable(&mut self, executable: impl Into<String>) -> &mut Self
+//
+// Given on the struct, it applies to the setters of every field. For Vec fields
+// with an `each` method, the one-at-a-time method converts its element too.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each = "arg", setter(into))]
+    args: Vec<String>,
+    current_dir: Option<PathBuf>,
+}
+
+#[derive(Builder)]
+#[builder(setter(into), typestate)]
+pub struct Server {
+    host: String,
+    port: u16,
+    root: Option<PathBuf>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .current_dir(PathBuf::from("/"))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+
+    let server = Server::builder()
+        .host("localhost")
+        .port(8080u16)
+        .root("/srv")
+        .build();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.root, Some(PathBuf::from("/srv")));
+}
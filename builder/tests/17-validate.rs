@@ -0,0 +1,58 @@
+// Invariants spanning several fields can be checked by a validation hook given
+// on the struct:
+//
+//     #[builder(validate = "path::to::function")]
+//
+// The function receives the assembled struct by reference and returns
+// `Result<(), E>` for any `E: Into<String>`. When it fails, `build()` returns
+// `Validation` with the message instead of the struct. In typestate mode the
+// hook makes `build()` return a `Result` as well.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "validate::range")]
+pub struct Range {
+    min: u32,
+    max: u32,
+}
+
+#[derive(Builder)]
+#[builder(typestate, validate = "validate::pool")]
+pub struct Pool {
+    min_idle: usize,
+    max_size: usize,
+}
+
+mod validate {
+    pub fn range(range: &super::Range) -> Result<(), String> {
+        if range.min <= range.max {
+            Ok(())
+        } else {
+            Err(format!("min {} exceeds max {}", range.min, range.max))
+        }
+    }
+
+    pub fn pool(pool: &super::Pool) -> Result<(), &'static str> {
+        if pool.min_idle <= pool.max_size {
+            Ok(())
+        } else {
+            Err("min_idle exceeds max_size")
+        }
+    }
+}
+
+fn main() {
+    let range = Range::builder().min(1).max(2).build().unwrap();
+    assert_eq!((range.min, range.max), (1, 2));
+
+    let err = Range::builder().min(3).max(2).build().err().unwrap();
+    assert!(matches!(err, RangeBuilderError::Validation(_)));
+    assert_eq!(err.to_string(), "min 3 exceeds max 2");
+
+    let pool = Pool::builder().min_idle(1).max_size(8).build().unwrap();
+    assert_eq!(pool.max_size, 8);
+
+    let err = Pool::builder().min_idle(8).max_size(1).build().err().unwrap();
+    assert_eq!(err.to_string(), "min_idle exceeds max_size");
+}
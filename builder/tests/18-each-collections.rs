@@ -0,0 +1,64 @@
+// `#[builder(each = "...")]` is not limited to Vec. The one-at-a-time method
+// extends the collection with a single item:
+//
+//   - HashMap<K, V> and BTreeMap<K, V> take the key and the value as two
+//     arguments,
+//   - HashSet<T>, BTreeSet<T>, VecDeque<T>, LinkedList<T> and BinaryHeap<T>
+//     take one element,
+//   - any other field type given an `each` method is expected to implement
+//     Extend, and the method accepts whatever it can be extended with.
+//
+// Like Vec fields, these fields start out empty and never have to be set.
+// Without `each`, a collection is set as a whole and is required like any
+// other field.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+#[derive(Builder)]
+pub struct Config {
+    #[builder(each = "env", setter(into))]
+    envs: HashMap<String, String>,
+    #[builder(each = "limit")]
+    limits: BTreeMap<&'static str, u32>,
+    #[builder(each = "feature")]
+    features: HashSet<&'static str>,
+    #[builder(each = "tag")]
+    tags: BTreeSet<u8>,
+    #[builder(each = "job")]
+    jobs: VecDeque<u32>,
+    #[builder(each = "push")]
+    text: String,
+    aliases: HashMap<String, String>,
+}
+
+fn main() {
+    let config = Config::builder()
+        .env("HOME", "/root")
+        .env(String::from("SHELL"), "/bin/sh")
+        .limit("cpu", 2)
+        .limit("mem", 512)
+        .feature("serde")
+        .tag(2)
+        .tag(1)
+        .job(1)
+        .job(2)
+        .push("hello")
+        .push(',')
+        .push(" world")
+        .aliases(HashMap::from([("ll".to_owned(), "ls -l".to_owned())]))
+        .build()
+        .unwrap();
+
+    assert_eq!(config.envs["HOME"], "/root");
+    assert_eq!(config.envs["SHELL"], "/bin/sh");
+    assert_eq!(config.limits.into_iter().collect::<Vec<_>>(), [("cpu", 2), ("mem", 512)]);
+    assert!(config.features.contains("serde"));
+    assert_eq!(config.tags.into_iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(config.jobs, [1, 2]);
+    assert_eq!(config.text, "hello, world");
+    assert_eq!(config.aliases["ll"], "ls -l");
+
+    let err = Config::builder().build().err().unwrap();
+    assert!(matches!(err, ConfigBuilderError::MissingField("aliases")));
+}
@@ -0,0 +1,32 @@
+// Mistakes in `builder` attributes are reported as compile errors pointing at
+// the offending attribute rather than as a panic of the macro. Errors from all
+// of the fields are reported together, so that every mistake shows up in one
+// compile.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Option<Vec<String>>,
+    #[builder(default)]
+    env: Vec<String>,
+    #[builder(eahc = "dir")]
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Layer {
+    #[builder(merge = "append", each = "include")]
+    includes: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Job {
+    #[builder(sub_builder)]
+    command: Command,
+}
+
+fn main() {}
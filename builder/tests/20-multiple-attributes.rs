@@ -0,0 +1,47 @@
+// Fields may carry attributes other than `builder`, in any order, and options
+// may be split across several `#[builder(...)]` attributes which are merged
+// together. Doc comments and `#[deprecated]` on a field are copied onto its
+// setter, so that a public builder passes `missing_docs` and only the use of
+// a deprecated setter is reported, not the code generated for the field.
+
+#![deny(missing_docs)]
+//! Builder of a command with documented fields.
+
+use derive_builder::Builder;
+
+/// A program to run.
+#[derive(Builder)]
+#[builder(vis = "pub")]
+pub struct Command {
+    /// Program to run.
+    pub executable: String,
+    #[builder(each = "arg")]
+    /// Arguments passed to the program.
+    #[builder(setter(into))]
+    #[allow(dead_code)]
+    pub args: Vec<String>,
+    #[builder(default = "8")]
+    #[doc = "Upper bound on the number of concurrent jobs."]
+    pub jobs: usize,
+    /// Whether to print every step.
+    #[deprecated(note = "use `log_level` instead")]
+    pub verbose: Option<bool>,
+}
+
+fn main() {
+    #[allow(deprecated)]
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build")
+        .arg("--release")
+        .verbose(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.jobs, 8);
+    #[allow(deprecated)]
+    let verbose = command.verbose;
+    assert_eq!(verbose, Some(true));
+}
@@ -0,0 +1,37 @@
+// The name and visibility of the generated items can be controlled from the
+// struct:
+//
+//     #[builder(
+//         name = "ConfigFactory",     // default: `{Struct}Builder`
+//         vis = "pub",                // default: private
+//         constructor = "factory",    // default: `builder`
+//         build_fn = "finish",        // default: `build`
+//     )]
+//
+// The error type follows the builder's name and visibility, so here it is
+// `pub enum ConfigFactoryError`. The fields of the builder are always private.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(name = "ConfigFactory", vis = "pub", constructor = "factory")]
+    #[builder(build_fn = "finish")]
+    pub struct Config {
+        pub name: String,
+        pub verbose: Option<bool>,
+    }
+}
+
+use config::{Config, ConfigFactory, ConfigFactoryError};
+
+fn main() {
+    let mut factory: ConfigFactory = Config::factory();
+    factory.name("app".to_owned());
+    let config = factory.finish().unwrap();
+    assert_eq!(config.name, "app");
+    assert_eq!(config.verbose, None);
+
+    let err = Config::factory().finish().err().unwrap();
+    assert!(matches!(err, ConfigFactoryError::MissingField("name")));
+}
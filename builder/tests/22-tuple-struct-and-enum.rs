@@ -0,0 +1,58 @@
+// Tuple structs get positional setters named `_0`, `_1`, ..., any of which can
+// be renamed with `#[builder(setter(name = "..."))]`.
+//
+// Enums get one builder per variant that has fields, named
+// `{Enum}{Variant}Builder` and created through `{variant}_builder()` on the
+// enum. Options on the enum apply to every variant, and options on a variant
+// apply to that variant only; `name` and `constructor` may only be given on
+// variants.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Rgb(#[builder(setter(name = "red"))] u8, u8, Option<u8>);
+
+#[derive(Debug, PartialEq, Builder)]
+#[builder(setter(into))]
+pub enum Message<T> {
+    Quit,
+    Text {
+        from: String,
+        #[builder(each = "line")]
+        lines: Vec<String>,
+    },
+    #[builder(constructor = "move_to", name = "MoveTo", pattern = "owned")]
+    Move(i32, i32),
+    Custom {
+        payload: Option<T>,
+    },
+}
+
+fn main() {
+    let rgb = Rgb::builder().red(255)._1(128).build().unwrap();
+    assert_eq!((rgb.0, rgb.1, rgb.2), (255, 128, None));
+
+    let err = Rgb::builder()._1(0).build().err().unwrap();
+    assert_eq!(err.to_string(), "field 0 is not set");
+
+    let text = Message::<()>::text_builder()
+        .from("alice")
+        .line("hello")
+        .line("world")
+        .build()
+        .unwrap();
+    assert_eq!(
+        text,
+        Message::Text {
+            from: "alice".to_owned(),
+            lines: vec!["hello".to_owned(), "world".to_owned()],
+        },
+    );
+
+    let moved: MoveTo<()> = Message::move_to();
+    let moved = moved._0(1)._1(-1).build().unwrap();
+    assert_eq!(moved, Message::Move(1, -1));
+
+    let custom = Message::custom_builder().payload(7u8).build().unwrap();
+    assert_eq!(custom, Message::Custom { payload: Some(7) });
+}
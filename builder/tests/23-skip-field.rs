@@ -0,0 +1,62 @@
+// A field marked `#[builder(skip)]` gets neither a setter nor storage in the
+// builder. `build()` fills it in with `Default::default()`, or with the given
+// expression which may refer to the other fields by name.
+//
+//     #[builder(skip)]
+//     #[builder(skip, default = "format!(\"{}:{}\", host, port)")]
+//
+// The expression is evaluated after every other field has been resolved, so
+// it sees the final values including their own defaults.
+//
+// A generic parameter only used by skipped fields, such as the marker of a
+// typed id, is still used by the builder.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+    #[builder(skip, default = r#"format!("{}:{}", host, port)"#)]
+    address: String,
+    #[builder(skip)]
+    cache: HashMap<String, Vec<u8>>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Point {
+    x: i32,
+    y: i32,
+    #[builder(skip, default = "x * x + y * y")]
+    norm: i32,
+}
+
+#[derive(Builder)]
+pub struct Id<T> {
+    value: u64,
+    #[builder(skip)]
+    marker: PhantomData<T>,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.address, "localhost:8080");
+    assert!(server.cache.is_empty());
+
+    let point = Point::builder().x(3).y(4).build();
+    assert_eq!(point.norm, 25);
+
+    let id: Id<Server> = Id::builder().value(7).build().unwrap();
+    assert_eq!(id.value, 7);
+    let _: PhantomData<Server> = id.marker;
+    assert_eq!(id.to_builder().build().unwrap().value, 7);
+}
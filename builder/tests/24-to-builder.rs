@@ -0,0 +1,81 @@
+// The builder can be created from an existing value to derive a modified copy
+// of it, by cloning its fields with `to_builder()`, which is only available
+// when every field is `Clone`. Under `#[builder(from)]` the builder also
+// implements `From`, so the value can be consumed with `From`/`Into` instead.
+// This is opt-in as the fields cannot be moved out of a struct implementing
+// `Drop`, for which `to_builder()` still works.
+//
+//     let builder = config.to_builder();
+//     let builder: ConfigBuilder = config.into();
+//
+// Every field is pre-populated, so `build()` succeeds without setting any of
+// them again. Skipped fields are computed anew by `build()`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone)]
+#[builder(from)]
+pub struct Config {
+    name: String,
+    #[builder(default = "80")]
+    port: u16,
+    proxy: Option<String>,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(skip, default = "args.len()")]
+    arg_count: usize,
+}
+
+#[derive(Builder)]
+#[builder(typestate, from)]
+pub struct Point<T> {
+    x: T,
+    y: T,
+}
+
+#[derive(Builder)]
+#[builder(from)]
+pub struct NotClone {
+    value: u8,
+}
+
+#[derive(Builder)]
+pub struct Guard {
+    name: String,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+
+fn main() {
+    let config = Config::builder()
+        .name("server".to_owned())
+        .arg("-v".to_owned())
+        .build()
+        .unwrap();
+
+    let copy = config.to_builder().port(8080).arg("-q".to_owned()).build().unwrap();
+    assert_eq!(copy.name, "server");
+    assert_eq!(copy.port, 8080);
+    assert_eq!(copy.proxy, None);
+    assert_eq!(copy.args, vec!["-v", "-q"]);
+    assert_eq!(copy.arg_count, 2);
+    assert_eq!(config.port, 80);
+
+    let mut builder = ConfigBuilder::from(config);
+    let config = builder.proxy("localhost".to_owned()).build().unwrap();
+    assert_eq!(config.proxy.as_deref(), Some("localhost"));
+
+    let point = Point { x: 1, y: 2 };
+    let builder: PointBuilder<i32, (i32,), (i32,)> = point.into();
+    let point = builder.y(3).build();
+    assert_eq!((point.x, point.y), (1, 3));
+
+    let mut builder: NotCloneBuilder = NotClone { value: 1 }.into();
+    assert_eq!(builder.build().unwrap().value, 1);
+
+    let guard = Guard::builder().name("lock".to_owned()).build().unwrap();
+    let copy = guard.to_builder().build().unwrap();
+    assert_eq!(copy.name, guard.name);
+}
@@ -0,0 +1,70 @@
+// With `#[builder(getters)]` the builder can be inspected before `build()`,
+// e.g. by code which fills in a partially set builder:
+//
+//     fn get_name(&self) -> Option<&String>
+//     fn name_is_set(&self) -> bool
+//
+// The getters are prefixed with `get_` to not collide with the setters.
+// Collection fields are always there to get, and count as set once they are
+// not empty, or once they differ from their default for other types given
+// `each`, such as a `String`. Positional fields get `get_0`, `get_1`, ...
+// like any other name.
+
+#![deny(warnings)]
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+#[builder(getters)]
+pub struct Request {
+    url: String,
+    timeout: Option<u32>,
+    #[builder(each = "header")]
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+    #[builder(each = "push")]
+    text: String,
+}
+
+fn apply_defaults(builder: &mut RequestBuilder) {
+    if !builder.timeout_is_set() {
+        builder.timeout(30);
+    }
+}
+
+#[derive(Builder)]
+#[builder(getters)]
+pub struct Size(u32, u32);
+
+fn main() {
+    let mut builder = Request::builder();
+    assert!(!builder.url_is_set());
+    assert_eq!(builder.get_url(), None);
+    assert!(!builder.headers_is_set());
+
+    builder
+        .url("https://example.com".to_owned())
+        .header("Accept".to_owned(), "*/*".to_owned());
+    assert!(builder.url_is_set());
+    assert_eq!(builder.get_url().map(String::as_str), Some("https://example.com"));
+    assert!(builder.headers_is_set());
+    assert_eq!(builder.get_headers().len(), 1);
+    assert!(!builder.body_is_set());
+    assert!(builder.get_body().is_empty());
+    assert!(!builder.text_is_set());
+    builder.push("hello").push(' ');
+    assert!(builder.text_is_set());
+    assert_eq!(builder.get_text(), "hello ");
+
+    apply_defaults(&mut builder);
+    assert_eq!(builder.get_timeout(), Some(&30));
+
+    let request = builder.build().unwrap();
+    assert_eq!(request.timeout, Some(30));
+
+    let mut size = Size::builder();
+    size._0(640);
+    assert_eq!(size.get_0(), Some(&640));
+    assert!(!size._1_is_set());
+}
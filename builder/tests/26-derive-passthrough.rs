@@ -0,0 +1,73 @@
+// With the `derive` feature, `#[builder(derive(...))]` adds derives onto the
+// generated builder. Deriving serde's traits lets a partially specified
+// config be loaded into the builder, overridden in code and then validated by
+// `build()`:
+//
+//     #[derive(Builder)]
+//     #[builder(derive(Debug, Deserialize, Serialize))]
+//     pub struct Config { ... }
+//
+// Fields missing from the input keep the builder's default, so collections
+// may be left out just like the other fields. The marker keeping the generic
+// parameters of the struct in use is not serialized.
+//
+// This test only runs under `cargo test --features derive`, which CI runs
+// along with the plain `cargo test`.
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+#[derive(Builder)]
+#[builder(derive(Debug, PartialEq, Deserialize, Serialize))]
+pub struct Config {
+    name: String,
+    #[builder(default = "80")]
+    port: u16,
+    proxy: Option<String>,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable", derive(Clone, Debug))]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Builder)]
+#[builder(derive(Deserialize, Serialize))]
+pub struct Labeled<T> {
+    label: String,
+    #[builder(skip)]
+    marker: PhantomData<T>,
+}
+
+fn main() {
+    let mut builder: ConfigBuilder = serde_json::from_str(r#"{ "port": 8080 }"#).unwrap();
+    let mut expected = ConfigBuilder::default();
+    expected.port(8080);
+    assert_eq!(builder, expected);
+
+    let config = builder.name("server".to_owned()).arg("-v".to_owned()).build().unwrap();
+    assert_eq!(config.name, "server");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.proxy, None);
+    assert_eq!(config.args, vec!["-v"]);
+
+    let mut builder = Config::builder();
+    builder.name("client".to_owned());
+    let json = serde_json::to_string(&builder).unwrap();
+    assert_eq!(
+        json,
+        r#"{"name":"client","port":null,"proxy":null,"args":[]}"#,
+    );
+
+    let mut builder: LabeledBuilder<u8> = serde_json::from_str(r#"{ "label": "a" }"#).unwrap();
+    assert_eq!(serde_json::to_string(&builder).unwrap(), r#"{"label":"a"}"#);
+    assert_eq!(builder.build().unwrap().label, "a");
+
+    let builder = Point::builder().x(1);
+    assert_eq!(format!("{:?}", builder), "PointBuilder { x: Some(1), y: None }");
+}
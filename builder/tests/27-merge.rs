@@ -0,0 +1,78 @@
+// With `#[builder(merge)]`, builders can be layered on top of each other, e.g.
+// a config file overridden by the environment overridden by the command line:
+//
+//     file.merge(env);              // fields set in `env` win
+//     let config = cli.or(file);    // fields set in `cli` win
+//
+// An unset field never overrides a set one. Collections count as set once
+// they are not empty, and are replaced as a whole unless the field asks for
+// `#[builder(merge = "append")]`. Other types given `each`, such as a
+// `String`, count as set once they differ from their default, and are
+// appended to by extending them with the other value.
+//
+// Without `#[builder(merge)]` there are no such methods, and fields may be
+// named `merge` or `or` like any other.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+#[builder(merge)]
+pub struct Config {
+    name: String,
+    #[builder(default = "80")]
+    port: u16,
+    proxy: Option<String>,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(merge = "append", each = "include")]
+    includes: Vec<String>,
+    #[builder(merge = "append", each = "env")]
+    envs: HashMap<String, String>,
+    #[builder(each = "push_motd")]
+    motd: String,
+    #[builder(merge = "append", each = "push_path")]
+    path: String,
+}
+
+#[derive(Builder)]
+pub struct Strategy {
+    merge: bool,
+    or: bool,
+}
+
+fn main() {
+    let mut file = Config::builder();
+    file.name("file".to_owned())
+        .port(8080)
+        .arg("-v".to_owned())
+        .include("a".to_owned())
+        .env("HOME".to_owned(), "/root".to_owned())
+        .push_motd("file")
+        .push_path("/usr/bin");
+
+    let mut env = Config::builder();
+    env.proxy("localhost".to_owned())
+        .include("b".to_owned())
+        .env("HOME".to_owned(), "/home".to_owned())
+        .env("USER".to_owned(), "me".to_owned())
+        .push_path(":/bin");
+    file.merge(env);
+
+    let mut cli = Config::builder();
+    cli.name("cli".to_owned()).arg("-q".to_owned()).include("c".to_owned());
+
+    let config = cli.or(file).build().unwrap();
+    assert_eq!(config.name, "cli");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.proxy.as_deref(), Some("localhost"));
+    assert_eq!(config.args, vec!["-q"]);
+    assert_eq!(config.includes, vec!["a", "b", "c"]);
+    assert_eq!(config.envs["HOME"], "/home");
+    assert_eq!(config.envs["USER"], "me");
+    assert_eq!(config.motd, "file");
+    assert_eq!(config.path, "/usr/bin:/bin");
+
+    let strategy = Strategy::builder().merge(true).or(false).build().unwrap();
+    assert!(strategy.merge && !strategy.or);
+}
@@ -0,0 +1,68 @@
+// With `#[builder(no_std)]` the generated code only refers to `core` and
+// `alloc`, so that the derive can be used in `#![no_std]` crates which have
+// an allocator:
+//
+//     #![no_std]
+//     extern crate alloc;
+//
+// The error type still implements `Display` and `Debug`, but not
+// `std::error::Error`.
+//
+// This test links std under a different name only to get a runtime for
+// `main`; `::std` paths do not resolve here.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as _;
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(no_std, validate = "Device::check")]
+pub struct Device {
+    name: String,
+    #[builder(default = "9600")]
+    baud: u32,
+    parity: Option<bool>,
+    #[builder(each = "pin")]
+    pins: Vec<u8>,
+    #[builder(each = "register")]
+    registers: BTreeMap<u8, u8>,
+}
+
+impl Device {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.pins.is_empty() {
+            return Err("no pins");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Builder)]
+#[builder(no_std, typestate)]
+pub struct Pin {
+    number: u8,
+}
+
+fn main() {
+    let device = Device::builder()
+        .name("uart".to_owned())
+        .pin(1)
+        .register(0, 0xff)
+        .build()
+        .unwrap();
+    assert_eq!(device.baud, 9600);
+    assert_eq!(device.parity, None);
+    assert_eq!(device.registers[&0], 0xff);
+
+    let err = Device::builder().build().err();
+    assert!(matches!(err, Some(DeviceBuilderError::MissingField("name"))));
+
+    assert_eq!(Pin::builder().number(3).build().number, 3);
+}
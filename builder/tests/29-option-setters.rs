@@ -0,0 +1,60 @@
+// The setter of an `Option<T>` field takes the inner `T`. Two more methods
+// make it possible to forward an `Option<T>` as is and to reset the field:
+//
+//     fn proxy_opt(&mut self, proxy: Option<String>) -> &mut Self
+//     fn clear_proxy(&mut self) -> &mut Self
+//
+// Their names can be changed with `setter(opt = "...", clear = "...")`, and
+// they follow `setter(into)` just like the setter does. A positional field
+// `_0` gets `_0_opt` and `clear_0`.
+
+#![deny(warnings)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Request {
+    url: String,
+    proxy: Option<String>,
+    #[builder(setter(into, opt = "maybe_timeout", clear = "no_timeout"))]
+    timeout: Option<u64>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Query {
+    text: String,
+    limit: Option<usize>,
+}
+
+#[derive(Builder)]
+pub struct Timeout(Option<u64>);
+
+fn main() {
+    let proxy = std::env::var("DOES_NOT_EXIST").ok();
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .proxy_opt(proxy)
+        .maybe_timeout(Some(30u32))
+        .build()
+        .unwrap();
+    assert_eq!(request.proxy, None);
+    assert_eq!(request.timeout, Some(30));
+
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .proxy("localhost".to_owned())
+        .timeout(10u32)
+        .clear_proxy()
+        .no_timeout()
+        .build()
+        .unwrap();
+    assert_eq!(request.proxy, None);
+    assert_eq!(request.timeout, None);
+
+    let query = Query::builder().limit(10).clear_limit().text("a".to_owned()).build();
+    assert_eq!(query.limit, None);
+
+    let timeout = Timeout::builder()._0_opt(Some(5)).clear_0().build().unwrap();
+    assert_eq!(timeout.0, None);
+}
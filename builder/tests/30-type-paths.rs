@@ -0,0 +1,60 @@
+// Option and Vec fields are recognized when spelled out by their full path in
+// std, core or alloc, while a type only named the same in another module is
+// left alone:
+//
+//     ::std::option::Option<T>, core::option::Option<T>, std::vec::Vec<T>
+//
+// `#[builder(kind = "option" | "vec" | "raw")]` overrides the classification,
+// for type aliases or a type of one's own called `Option`.
+
+use derive_builder::Builder;
+
+mod custom {
+    #[derive(Debug, PartialEq)]
+    pub struct Option<T>(pub T);
+}
+
+type Maybe<T> = Option<T>;
+type List<T> = Vec<T>;
+
+#[derive(Builder)]
+pub struct Command {
+    name: ::std::option::Option<String>,
+    current_dir: core::option::Option<String>,
+    #[builder(each = "arg")]
+    args: ::std::vec::Vec<String>,
+    #[builder(each = "env")]
+    env: std::collections::HashMap<String, String>,
+    custom: custom::Option<u8>,
+    #[builder(kind = "option")]
+    timeout: Maybe<u64>,
+    #[builder(kind = "vec", each = "file")]
+    files: List<String>,
+    #[builder(kind = "raw")]
+    exit_code: Option<i32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("-v".to_owned())
+        .env("A".to_owned(), "1".to_owned())
+        .custom(custom::Option(1))
+        .timeout(10)
+        .file("a".to_owned())
+        .exit_code(None)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.name, None);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.args, vec!["-v"]);
+    assert_eq!(command.env["A"], "1");
+    assert_eq!(command.custom, custom::Option(1));
+    assert_eq!(command.timeout, Some(10));
+    assert_eq!(command.files, vec!["a"]);
+    assert_eq!(command.exit_code, None);
+
+    // `exit_code` is required as it is taken as a plain type
+    let err = Command::builder().custom(custom::Option(1)).build().err();
+    assert!(matches!(err, Some(CommandBuilderError::MissingField("exit_code"))));
+}
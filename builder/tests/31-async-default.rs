@@ -0,0 +1,101 @@
+// A field may take its default from an async function, such as one loading a
+// resource from disk:
+//
+//     #[builder(default_with = "load_token")]
+//     token: String,
+//
+// which makes the builder get a `build_async()` awaiting the function when the
+// field was not set. The plain `build()` cannot do so, and fails with
+// `MissingField` for such a field instead.
+//
+// A function which may fail, returning `Result<T, E>`, is given as
+// `try_default_with` instead. Its error is passed on by `build_async()` as
+// `DefaultFailed`, naming the field and keeping the error as its `source()`.
+
+use derive_builder::Builder;
+use std::error::Error;
+use std::future::Future;
+use std::io;
+use std::pin::pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+#[derive(Builder)]
+pub struct Client {
+    url: String,
+    #[builder(default_with = "load_token")]
+    token: String,
+    #[builder(default_with = "defaults::retries")]
+    retries: u32,
+    #[builder(try_default_with = "load_config")]
+    config: String,
+}
+
+async fn load_token() -> String {
+    "secret".to_owned()
+}
+
+async fn load_config() -> io::Result<String> {
+    Err(io::Error::new(io::ErrorKind::NotFound, "client.toml not found"))
+}
+
+mod defaults {
+    pub async fn retries() -> u32 {
+        3
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let mut builder = Client::builder();
+    builder
+        .url("https://example.com".to_owned())
+        .retries(5)
+        .config("verbose = true".to_owned());
+    let client = block_on(builder.build_async()).unwrap();
+    assert_eq!(client.token, "secret");
+    assert_eq!(client.retries, 5);
+
+    let mut builder = Client::builder();
+    builder
+        .url("https://example.com".to_owned())
+        .token("other".to_owned())
+        .config("".to_owned());
+    let client = block_on(builder.build_async()).unwrap();
+    assert_eq!(client.token, "other");
+    assert_eq!(client.retries, 3);
+
+    let mut builder = Client::builder();
+    builder.url("https://example.com".to_owned());
+    let err = block_on(builder.build_async()).err().unwrap();
+    assert!(matches!(err, ClientBuilderError::DefaultFailed("config", _)));
+    assert_eq!(
+        err.to_string(),
+        "default of field config failed: client.toml not found",
+    );
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::NotFound);
+
+    let err = Client::builder()
+        .url("https://example.com".to_owned())
+        .build()
+        .err();
+    assert!(matches!(err, Some(ClientBuilderError::MissingField("token"))));
+}
@@ -0,0 +1,22 @@
+// The builder methods are generated in the order the fields are declared, and
+// two of them ending up with the same name is reported on the name which
+// comes second, rather than one of them silently replacing the other.
+//
+// The only exception is an `each` method named like the field's own setter,
+// which takes the place of the setter as test 07 expects.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "arg")]
+    extra_args: Vec<String>,
+    #[builder(setter(name = "env"))]
+    environment: Vec<String>,
+    env: Vec<String>,
+    build: bool,
+}
+
+fn main() {}
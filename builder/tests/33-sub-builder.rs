@@ -0,0 +1,129 @@
+// A field whose type derives `Builder` itself can be given
+// `#[builder(sub_builder)]`, which stores the builder of that type in place
+// of the value and hands it out to be filled in:
+//
+//     fn tls(&mut self) -> &mut TlsBuilder
+//
+// The parent's `build()` builds the field in turn, reporting the errors of
+// the inner builder as `SubBuilder` errors naming the field, which keep the
+// inner error as their `source()` to be matched on in turn. The
+// builder is expected to be `{Type}Builder` with a `build` method, unless they
+// are named by `#[builder(sub_builder(builder = "...", build_fn = "..."))]`,
+// or `#[builder(sub_builder = "...")]` for the builder only. With
+// `#[builder(merge)]`, the builders of the fields are merged in turn, so they
+// need `#[builder(merge)]` as well.
+//
+// The builder of a field need not be derived: any type implementing `Default`
+// for the parent's builder to start from, with a build method returning a
+// `Result` whose error implements `std::error::Error`, will do. `to_builder()`
+// and `From` create the builders of the fields from their values with `From`,
+// so they are only available when those builders implement `From` as well,
+// e.g. through `#[builder(from)]`.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder, Clone)]
+#[builder(merge)]
+pub struct Server {
+    host: String,
+    #[builder(sub_builder)]
+    tls: Tls,
+    #[builder(sub_builder(builder = "limits::Builder", build_fn = "finish"))]
+    limits: limits::Limits,
+}
+
+#[derive(Builder, Clone)]
+#[builder(merge, from)]
+pub struct Tls {
+    cert: String,
+    #[builder(default)]
+    verify: bool,
+}
+
+mod limits {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Clone)]
+    #[builder(
+        name = "Builder",
+        build_fn = "finish",
+        vis = "pub",
+        pattern = "owned",
+        merge,
+        from
+    )]
+    pub struct Limits {
+        #[builder(default = "100")]
+        pub connections: u32,
+    }
+}
+
+#[derive(Builder, Clone)]
+#[builder(from)]
+pub struct Proxy {
+    #[builder(sub_builder = "Handwritten")]
+    upstream: Upstream,
+}
+
+#[derive(Clone)]
+pub struct Upstream {
+    host: String,
+}
+
+#[derive(Default)]
+pub struct Handwritten {
+    host: Option<String>,
+}
+
+impl Handwritten {
+    pub fn host(&mut self, host: &str) -> &mut Self {
+        self.host = Some(host.to_owned());
+        self
+    }
+
+    pub fn build(&mut self) -> Result<Upstream, std::fmt::Error> {
+        let host = self.host.take().ok_or(std::fmt::Error)?;
+        Ok(Upstream { host })
+    }
+}
+
+fn main() {
+    let mut builder = Server::builder();
+    builder.host("localhost".to_owned());
+    builder.tls().cert("cert.pem".to_owned()).verify(true);
+    let server = builder.build().unwrap();
+    assert_eq!(server.tls.cert, "cert.pem");
+    assert!(server.tls.verify);
+    assert_eq!(server.limits.connections, 100);
+
+    let mut builder = server.to_builder();
+    *builder.limits() = limits::Limits::builder().connections(10);
+    let server = builder.build().unwrap();
+    assert_eq!(server.tls.cert, "cert.pem");
+    assert_eq!(server.limits.connections, 10);
+
+    let err = Server::builder().host("localhost".to_owned()).build().err().unwrap();
+    assert_eq!(err.to_string(), "tls: field cert is not set");
+    assert!(matches!(err, ServerBuilderError::SubBuilder("tls", _)));
+    let source = err.source().unwrap().downcast_ref::<TlsBuilderError>();
+    assert!(matches!(source, Some(TlsBuilderError::MissingField("cert"))));
+
+    let mut base = Server::builder();
+    base.host("localhost".to_owned());
+    base.tls().cert("base.pem".to_owned());
+    let mut overrides = Server::builder();
+    overrides.tls().verify(true);
+    base.merge(overrides);
+    let server = base.build().unwrap();
+    assert_eq!(server.tls.cert, "base.pem");
+    assert!(server.tls.verify);
+
+    let mut builder = Proxy::builder();
+    builder.upstream().host("localhost");
+    let proxy = builder.build().unwrap();
+    assert_eq!(proxy.upstream.host, "localhost");
+
+    let err = Proxy::builder().build().err().unwrap();
+    assert!(matches!(err, ProxyBuilderError::SubBuilder("upstream", _)));
+}
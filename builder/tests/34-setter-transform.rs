@@ -0,0 +1,47 @@
+// `setter(transform = |...| ...)` gives a setter the typed arguments of the
+// closure, and sets the field to what its body makes of them:
+//
+//     #[builder(setter(transform = |start: u16, end: u16| start..end))]
+//     ports: Range<u16>,
+//
+//     builder.ports(8000, 8100);
+//
+// The closure takes the place of `setter(into)`, and works the same for the
+// required setters of a typestate builder.
+
+use derive_builder::Builder;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::ops::Range;
+use std::time::Duration;
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Server {
+    #[builder(setter(transform = |start: u16, end: u16| start..end))]
+    ports: Range<u16>,
+    #[builder(setter(transform = |secs: u64| -> Duration { Duration::from_secs(secs) }))]
+    timeout: Option<Duration>,
+    name: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    #[builder(setter(transform = |ip: [u8; 4], port: u16| SocketAddr::from((Ipv4Addr::from(ip), port))))]
+    address: SocketAddr,
+}
+
+fn main() {
+    let server = Server::builder()
+        .ports(8000, 8100)
+        .timeout(30)
+        .name("server")
+        .build()
+        .unwrap();
+    assert_eq!(server.ports, 8000..8100);
+    assert_eq!(server.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(server.name, "server");
+
+    let client = Client::builder().address([127, 0, 0, 1], 80).build();
+    assert_eq!(client.address.to_string(), "127.0.0.1:80");
+}
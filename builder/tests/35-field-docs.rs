@@ -0,0 +1,66 @@
+// The `///` comments of a field are copied onto its setter and `each` method,
+// and the builder and its `build` get a doc comment naming the fields which
+// need to be set:
+//
+//     /// Builder of [`Command`], which needs `executable` to be set.
+//     pub struct CommandBuilder { ... }
+//
+//     /// Builds the [`Command`], failing with `MissingField` unless
+//     /// `executable` is set.
+//     pub fn build(&mut self) -> Result<Command, CommandBuilderError>
+//
+// The other generated items get a short doc comment of their own, so that a
+// public builder of a documented struct passes `missing_docs`.
+
+#![deny(missing_docs)]
+//! Builders documented from their fields.
+
+use derive_builder::Builder;
+
+/// A program to run.
+#[derive(Builder)]
+#[builder(vis = "pub", getters)]
+pub struct Command {
+    /// Program to run.
+    pub executable: String,
+    /// Arguments passed to the program.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+    /// Directory to run the program in.
+    pub current_dir: Option<String>,
+    /// Whether to print every step.
+    verbose: Option<bool>,
+}
+
+/// A scheduled job.
+#[derive(Builder)]
+#[builder(typestate, pattern = "owned", vis = "pub")]
+pub struct Job {
+    /// Name shown in the logs.
+    pub name: String,
+    /// Number of retries.
+    retries: u32,
+    /// Extra labels.
+    pub labels: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .verbose(true)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.verbose, Some(true));
+
+    let job = Job::builder()
+        .name("nightly".to_owned())
+        .retries(3)
+        .build();
+    assert_eq!(job.name, "nightly");
+    assert_eq!(job.retries, 3);
+    assert!(job.labels.is_empty());
+}
@@ -0,0 +1,22 @@
+// The `#[deprecated]` of a field is copied onto its setter, so calling the
+// setter is reported like any other use of the field. This is a compile_fail
+// test, whose only error is the call in `main`: the code generated for the
+// deprecated field does not trip the lint itself.
+
+#![deny(deprecated)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[deprecated(note = "use `log_level` instead")]
+    verbose: Option<bool>,
+}
+
+fn main() {
+    let _ = Command::builder()
+        .executable("cargo".to_owned())
+        .verbose(true)
+        .build();
+}
@@ -0,0 +1,14 @@
+// Without the `derive` feature, `#[builder(derive(...))]` is reported at
+// derive time rather than ignored, naming the feature to enable. This is a
+// compile_fail test which only runs when the feature is off; with it on,
+// `26-derive-passthrough.rs` runs instead.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Debug))]
+pub struct Config {
+    name: String,
+}
+
+fn main() {}
@@ -8,6 +8,36 @@ fn tests() {
     t.pass("tests/05-method-chaining.rs");
     t.pass("tests/06-optional-field.rs");
     t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
     //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-generic-struct.rs");
+    t.pass("tests/11-builder-error.rs");
+    t.pass("tests/12-typestate.rs");
+    t.compile_fail("tests/13-typestate-missing-field.rs");
+    t.pass("tests/14-field-default.rs");
+    t.pass("tests/15-builder-pattern.rs");
+    t.pass("tests/16-setter-into.rs");
+    t.pass("tests/17-validate.rs");
+    t.pass("tests/18-each-collections.rs");
+    t.compile_fail("tests/19-attribute-errors.rs");
+    t.pass("tests/20-multiple-attributes.rs");
+    t.pass("tests/21-builder-naming.rs");
+    t.pass("tests/22-tuple-struct-and-enum.rs");
+    t.pass("tests/23-skip-field.rs");
+    t.pass("tests/24-to-builder.rs");
+    t.pass("tests/25-getters.rs");
+    #[cfg(feature = "derive")]
+    t.pass("tests/26-derive-passthrough.rs");
+    t.pass("tests/27-merge.rs");
+    t.pass("tests/28-no-std.rs");
+    t.pass("tests/29-option-setters.rs");
+    t.pass("tests/30-type-paths.rs");
+    t.pass("tests/31-async-default.rs");
+    t.compile_fail("tests/32-method-collisions.rs");
+    t.pass("tests/33-sub-builder.rs");
+    t.pass("tests/34-setter-transform.rs");
+    t.pass("tests/35-field-docs.rs");
+    t.compile_fail("tests/36-deprecated-setter.rs");
+    #[cfg(not(feature = "derive"))]
+    t.compile_fail("tests/37-derive-feature-off.rs");
 }